@@ -1,44 +1,265 @@
+action-add-label = Add
+action-add-tracker = Add
+action-apply = Apply
+action-cancel = Cancel
 action-close = Close
 action-copy-magnet = Copy Magnet Link
+action-delete-view = Delete
+action-details = Details…
+action-edit-labels = Labels…
+action-edit-trackers = Edit Trackers…
 action-open = Open torrent
 action-open-description = Open a torrent from a file on your computer
+action-open-data = Open
+action-open-file = Open File
 action-pause = Pause
+action-pause-folder = Pause Downloads
 action-prefs = Preferences
 action-prefs-description = Set download folder and other preferences
+action-queue-move-bottom = Move to Bottom
+action-queue-move-down = Move Down
+action-queue-move-top = Move to Top
+action-queue-move-up = Move Up
 action-quit = Quit
+action-reannounce = Ask Trackers for More Peers
 action-remove = Remove
+action-remove-and-delete = Remove and Delete Files
+action-remove-and-delete-menu = Remove and Delete Files…
+action-remove-menu = Remove…
+action-remove-tracker = Remove
+action-rename = Rename…
 action-resume = Resume
+action-invert-selection = Invert
+action-save = Save
+action-save-view = Save the current filter and search as a view
+action-replace-tracker = Replace
+action-select-all = All
+action-select-by-type = Select by Type
 action-select-files = Select Files to Download
+action-select-none = None
+action-set-location = Set Location…
+action-show-in-folder = Show in Folder
 action-show-in-filemanager = Show in File Browser
+action-start-now = Start Now
+action-undo = Undo
+action-verify = Verify Local Data
 advanced-preferences-title = Advanced
 all-files-filter-description = All files
 appmenu-tooltip = Application menu
-filter-tooltip = Filter
+background-reason = Keep downloading and seeding torrents after the window is closed
+details-added = Added:
+details-comment = Comment:
+details-completed = Completed:
+details-creator = Created by:
+details-downloaded = Downloaded:
+details-error = Error:
+details-files = Files
+details-general = General
+details-hash = Hash:
+details-location = Location:
+details-peers = Peers
+details-piece-count = Pieces:
+details-piece-map-tooltip = Downloaded pieces are filled in, pieces no connected peer has are shown in red
+details-pieces = Pieces
+details-pieces-value = { $count } × { $size }
+details-ratio = Ratio:
+details-size = Size:
+details-trackers = Trackers
+details-uploaded = Uploaded:
+filter-tooltip = Filter by Label
 filter-all = All
 filter-downloading = Downloading
 filter-seeding = Seeding
 filter-paused = Paused
+filter-errors = With Errors
+filter-queued = Queued
+filter-checking = Verifying
+filter-active = Active
+filter-inactive = Inactive
+filter-complete = Complete
+filter-incomplete = Incomplete
+filter-all-labels = Any Label
+filter-label = { $label } ({ $count })
+file-column-name = Name
+file-column-priority = Priority
+file-column-progress = Progress
+file-column-size = Size
+file-priority-tooltip = Download priority
+file-rename-tooltip = Click to rename
+file-search-placeholder = Search Files
+file-type-audio = Audio
+file-type-image = Images
+file-type-text = Documents
+file-type-video = Video
 general-preferences-title = General
 header-desktop-integration = Desktop Integration
+header-notifications = Notifications
+header-peers = Peers
 header-security = Security
 heading-download-location = Download Location
 heading-limits = Limits
+label-geoip-database = Country database:
 label-download-speed-limit = Download speed limit (KBps):
 label-hide-on-close = Continue downloads when closed:
+label-notify-added = Torrents added elsewhere:
+label-notify-disk-space = Download folder running out of space:
+label-notify-errors = Torrent errors:
+label-notify-finished = Finished downloads:
 label-max-downloads = Max simultaneous downloads:
 label-only-encrypted-peers = Only connect to encrypted peers:
 label-port-number = Port number:
 label-random-port = Randomise BitTorrent port on launch:
+label-start-on-login = Start in the background at login:
 label-upload-speed-limit = Upload speed limit (KBps):
+labels-clear-folder-tooltip = Don’t use a separate download folder
+labels-empty = No labels yet
+labels-entry-placeholder = Labels, separated by commas (optional)
+labels-folder-description = New torrents are downloaded to the folder of their first label that has one.
+labels-folder-title = Download Folder for “{ $label }”
+labels-folder-tooltip = Choose a download folder for this label
+labels-new-placeholder = New labels, separated by commas
+labels-title = Labels
 magnet-copied-notification = Magnet link copied
+peer-column-address = Address
+peer-column-client = Client
+peer-column-country = Country
+peer-column-download = Down
+peer-column-flags = Flags
+peer-column-port = Port
+peer-column-progress = Progress
+peer-column-source = From
+peer-column-upload = Up
+peer-flag-choked = Choked
+peer-flag-encrypted = Encrypted
+peer-flag-interested = Interested
+peer-flag-utp = µTP
+peer-source-dht = DHT
+peer-source-incoming = Incoming
+peer-source-lpd = Local network
+peer-source-pex = PEX
+peer-source-tracker = Tracker
+notification-added-title = Torrent added
+notification-background-body = Torrents keep downloading and seeding until you quit.
+notification-background-title = Torrential is running in the background
+notification-disk-space-body = Only { $free } left in { $folder }. Downloads may stop when it fills up.
+notification-disk-space-title = Download folder nearly full
+notification-error-body = { $name }: { $error }
+notification-error-title = Torrent error
+notification-finished-title = Download finished
 no-torrents-subtitle = Add a torrent file to begin downloading.
 no-torrents-title = No Torrents Added
 preferences-title = Preferences
-state-checking = Checking
+priority-high = High
+priority-low = Low
+priority-mixed = Mixed
+priority-normal = Normal
+query-error-invalid-value = “{ $value }” isn’t a valid value for { $field }:
+query-error-missing-value = Type something to search for after { $field }:
+query-error-unknown-field = There’s no search field called { $field }:
+query-error-unterminated-quote = A quote is missing its closing “"”
+queue-menu = Queue
+queue-position = #{ $position }
+queue-position-tooltip = Position in the queue
+rename-error-duplicate = An item with that name already exists
+rename-error-empty = The name cannot be empty
+rename-error-failed = The name couldn’t be changed: { $error }
+rename-error-reserved = The name cannot be “.” or “..”
+rename-error-slash = The name cannot contain “/”
+search-tooltip = Search by name, or narrow down with tracker:, label:, state:, file:, size:>4G, ratio:<1 or added:<7d. Put - in front of a term to exclude it.
+set-location-move-data = Move data to the new folder
+set-location-move-data-description = Existing downloaded data will be moved to the chosen folder.
+set-location-repoint-description = Use this if the data is already in the chosen folder. Nothing will be moved.
+set-location-title = Set Location
+sidebar-folders = Folders
+sidebar-trackers = Trackers
+sidebar-views = Saved Views
+sort-ascending = Ascending
+sort-date-added = Date Added
+sort-download-speed = Download Speed
+sort-eta = Time Remaining
+sort-name = Name
+sort-progress = Progress
+sort-queue-position = Queue Position
+sort-ratio = Ratio
+sort-size = Size
+sort-state = State
+sort-tooltip = Sort
+sort-upload-speed = Upload Speed
 state-paused = Paused
-state-waiting = Waiting in queue
+status-local-error = Error: { $message }
+status-moving = Moving data to { $location }…
+status-queued = Queued, position { $position }
+status-stalled-no-peers = Stalled, no peers connected
+status-tracker-error = Tracker error: { $message }
+status-tracker-warning = Tracker warning: { $message }
+status-verify-queued = Waiting to verify local data
+status-verifying = Verifying local data ({ $percent }%)
+tooltip-geoip-database = A CSV file of IP address ranges and country codes
+tooltip-reannounce = Announce to the trackers now rather than waiting for the next scheduled announce
 tooltip-unlimited-hint = 0 means unlimited
+torrent-recovery-tooltip = Fix this torrent
 torrent-files-filter-description = Torrent files
+tracker-url-placeholder = Announce URL
+view-move-down = Move Down
+view-move-up = Move Up
+view-name-duplicate = There’s already a view with that name
+view-name-placeholder = Name
+view-rename-title = Rename View
+view-save-description = The current filter, label and search will be shown in the sidebar under this name.
+view-save-title = Save View
+
+file-selection-summary =
+    Selected: { $files ->
+        [one] 1 file
+        *[other] { $files } files
+    }, { $selected } of { $total }
+
+details-tracker-subtitle = Tier { $tier } — { $result } — { $seeders } seeders, { $leechers } leechers
+details-tracker-status = Next announce: { $next } — Scrape: { $scrape }
+details-tracker-edit-failed = The trackers couldn’t be changed: { $error }
+details-tracker-tiers-unsupported = this version of Transmission can only put each added tracker in a new tier of its own
+
+details-peers-from = From tracker: { $tracker } · DHT: { $dht } · PEX: { $pex } · LPD: { $lpd } · Incoming: { $incoming }
+
+status-downloading =
+    { $sending } of { $peers ->
+        [one] 1 peer
+        *[other] { $peers } peers
+    } — ⬇{ $down }/s ⬆{ $up }/s — { $remaining } remaining
+status-fetching-metadata =
+    Fetching metadata ({ $percent }%) from { $peers ->
+        [one] 1 peer
+        *[other] { $peers } peers
+    }
+status-finished = Finished — ratio { $ratio }
+status-seeding =
+    Seeding to { $peers ->
+        [one] 1 peer
+        *[other] { $peers } peers
+    } — ⬆{ $up }/s — ratio { $ratio }
+status-stalled =
+    Stalled — connected to { $peers ->
+        [one] 1 peer
+        *[other] { $peers } peers
+    }
+
+remove-title =
+    Remove { $count ->
+        [one] this torrent
+        *[other] { $count } torrents
+    }?
+remove-delete-title =
+    Remove { $count ->
+        [one] this torrent and delete its files
+        *[other] { $count } torrents and delete their files
+    }?
+remove-description = The downloaded data ({ $size }) will be kept on disk.
+remove-delete-description = { $size } of downloaded data will be permanently deleted. This cannot be undone.
+torrents-removed-notification =
+    { $count ->
+        [one] Torrent removed
+        *[other] { $count } torrents removed
+    }
 
 seconds = 
     { $seconds -> 
@@ -59,4 +280,4 @@ days =
     { $days -> 
         [one] 1 day
         *[other] { $days } days
-    }
\ No newline at end of file
+    }
@@ -0,0 +1,128 @@
+use crate::fl;
+use gtk::gio;
+use gtk::glib::{self, VariantDict};
+use gtk::prelude::{ApplicationCommandLineExt, ApplicationExt, SettingsExt, ToVariant};
+use relm4::gtk;
+use std::path::{Path, PathBuf};
+
+const APP_ID: &str = "com.github.davidmhewitt.torrential";
+
+/// Command line flag for starting without showing the window
+pub const BACKGROUND_FLAG: &str = "--background";
+
+/// Whether we're running inside a Flatpak sandbox, where the Background portal decides
+/// whether the app may keep running without a window or start at login
+pub fn is_sandboxed() -> bool {
+    Path::new("/.flatpak-info").exists()
+}
+
+/// Handle our own command line rather than letting every launch activate the app. Launching
+/// again while already running brings the window back, unless it's another `--background`
+/// launch (e.g. at login), which shouldn't put the window in front of the user.
+pub fn handle_command_line() {
+    let app = relm4::main_application();
+    app.set_flags(app.flags() | gio::ApplicationFlags::HANDLES_COMMAND_LINE);
+    app.connect_command_line(|app, command_line| {
+        let background = command_line
+            .arguments()
+            .into_iter()
+            .any(|arg| arg == BACKGROUND_FLAG);
+        // The first launch always activates, to start up with or without the window
+        if !(background && command_line.is_remote()) {
+            app.activate();
+        }
+        glib::ExitCode::SUCCESS
+    });
+}
+
+fn start_on_login() -> bool {
+    let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+    settings.boolean("start-on-login")
+}
+
+/// Ask the Background portal to let us keep running with the window hidden. Outside the
+/// sandbox nothing stops us, so there's nobody to ask.
+///
+/// Every request also sets whether we're started at login, so this always passes on the
+/// current preference.
+pub fn request_background() {
+    if !is_sandboxed() {
+        return;
+    }
+
+    let Some(connection) = relm4::main_application().dbus_connection() else {
+        return;
+    };
+
+    let options = VariantDict::new(None);
+    options.insert_value("handle_token", &"torrential_background".to_variant());
+    options.insert_value("reason", &fl!("background-reason").to_variant());
+    options.insert_value("autostart", &start_on_login().to_variant());
+    options.insert_value(
+        "commandline",
+        &vec![env!("CARGO_PKG_NAME"), BACKGROUND_FLAG].to_variant(),
+    );
+
+    glib::MainContext::default().spawn_local(async move {
+        let result = connection
+            .call_future(
+                Some("org.freedesktop.portal.Desktop"),
+                "/org/freedesktop/portal/desktop",
+                "org.freedesktop.portal.Background",
+                "RequestBackground",
+                Some(&("", options.end()).to_variant()),
+                None,
+                gio::DBusCallFlags::NONE,
+                -1,
+            )
+            .await;
+
+        if let Err(err) = result {
+            log::error!("Error requesting permission to run in background: {}", err);
+        }
+    });
+}
+
+/// Register or unregister starting at login to match the preference
+pub fn update_autostart() {
+    if is_sandboxed() {
+        request_background();
+        return;
+    }
+
+    let path = autostart_file();
+    let result = if start_on_login() {
+        std::fs::create_dir_all(path.parent().expect("Autostart file is in a folder"))
+            .and_then(|_| std::fs::write(&path, autostart_entry()))
+    } else if path.exists() {
+        std::fs::remove_file(&path)
+    } else {
+        Ok(())
+    };
+
+    if let Err(err) = result {
+        log::error!("Error updating autostart file {}: {}", path.display(), err);
+    }
+}
+
+fn autostart_file() -> PathBuf {
+    glib::user_config_dir()
+        .join("autostart")
+        .join(format!("{}.desktop", APP_ID))
+}
+
+fn autostart_entry() -> String {
+    let exec = std::env::current_exe()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| env!("CARGO_PKG_NAME").to_string());
+
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Torrential\n\
+         Icon={}\n\
+         Exec=\"{}\" {}\n\
+         X-GNOME-Autostart-enabled=true\n",
+        APP_ID, exec, BACKGROUND_FLAG
+    )
+}
@@ -0,0 +1,785 @@
+use crate::fl;
+use crate::geoip::GeoIpDatabase;
+use crate::peers_view::PeersView;
+use crate::piece_map::{decode_pieces, PieceMap};
+use crate::rpc::{TorrentDetails, TorrentPeers, TorrentPieces, TorrentTrackers};
+use crate::utils::format_ratio;
+use gtk::prelude::{
+    BoxExt, ButtonExt, EditableExt, EntryExt, GridExt, GtkWindowExt, ListBoxRowExt, OrientableExt,
+    SettingsExt, WidgetExt,
+};
+use gtk::{gio, glib, pango};
+use relm4::{gtk, ComponentParts, ComponentSender, SimpleComponent};
+use std::path::Path;
+use transmission_client::TorrentFiles;
+
+/// The pages of the details dialog, named after their `gtk::Stack` children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailsPage {
+    General,
+    Trackers,
+    Peers,
+    Pieces,
+    Files,
+}
+
+impl DetailsPage {
+    fn name(&self) -> &'static str {
+        match self {
+            DetailsPage::General => "general",
+            DetailsPage::Trackers => "trackers",
+            DetailsPage::Peers => "peers",
+            DetailsPage::Pieces => "pieces",
+            DetailsPage::Files => "files",
+        }
+    }
+
+    fn from_name(name: &str) -> Self {
+        match name {
+            "trackers" => DetailsPage::Trackers,
+            "peers" => DetailsPage::Peers,
+            "pieces" => DetailsPage::Pieces,
+            "files" => DetailsPage::Files,
+            _ => DetailsPage::General,
+        }
+    }
+}
+
+pub struct DetailsDialogModel {
+    torrent_hash: String,
+    torrent_id: i32,
+    torrent_name: String,
+    page: DetailsPage,
+    general_values: Vec<gtk::Label>,
+    trackers_list: gtk::ListBox,
+    tracker_rows: Vec<TrackerRow>,
+    trackers: Vec<(i32, String)>,
+    selected_tracker: Option<usize>,
+    tracker_entry: gtk::Entry,
+    tracker_error: Option<String>,
+    peers_view: PeersView,
+    peers_summary: String,
+    geoip: Option<GeoIpDatabase>,
+    /// The database file last loaded, or being loaded
+    geoip_path: String,
+    settings: gio::Settings,
+    files_list: gtk::ListBox,
+    pieces_summary: String,
+    piece_map: PieceMap,
+    visible: bool,
+}
+
+#[derive(Debug)]
+pub enum DetailsDialogInput {
+    Open(String, i32, String, DetailsPage),
+    Close,
+    Refresh,
+    PageChanged(String),
+    GeneralChanged(Box<TorrentDetails>),
+    TrackersChanged(TorrentTrackers),
+    PeersChanged(TorrentPeers),
+    GeoIpLoaded(String, Option<GeoIpDatabase>),
+    PiecesChanged(TorrentPieces),
+    FilesChanged(TorrentFiles),
+    TrackerSelected(Option<usize>),
+    AddTracker,
+    ReplaceTracker,
+    RemoveTracker,
+    TrackerEditFailed(String),
+    Reannounce,
+}
+
+#[derive(Debug)]
+pub enum DetailsDialogOutput {
+    GetGeneral(i32),
+    GetTrackers(i32),
+    GetPeers(i32),
+    GetPieces(i32),
+    GetFiles(i32),
+    SetTrackerList(String, i32, String), // torrent_hash, torrent_id, tracker_list
+    Reannounce(String),
+}
+
+/// Rows of the General page, in display order
+fn general_headings() -> Vec<String> {
+    vec![
+        fl!("details-size"),
+        fl!("details-downloaded"),
+        fl!("details-uploaded"),
+        fl!("details-ratio"),
+        fl!("details-added"),
+        fl!("details-completed"),
+        fl!("details-location"),
+        fl!("details-hash"),
+        fl!("details-piece-count"),
+        fl!("details-comment"),
+        fl!("details-creator"),
+        fl!("details-error"),
+    ]
+}
+
+fn general_values(torrent: &TorrentDetails) -> Vec<String> {
+    vec![
+        glib::format_size(torrent.total_size as u64).to_string(),
+        glib::format_size(torrent.downloaded_ever as u64).to_string(),
+        glib::format_size(torrent.uploaded_ever as u64).to_string(),
+        format_ratio(torrent.upload_ratio),
+        format_date(torrent.added_date as i64),
+        format_date(torrent.done_date as i64),
+        torrent.download_dir.clone(),
+        torrent.hash_string.clone(),
+        fl!(
+            "details-pieces-value",
+            count = torrent.piece_count,
+            size = glib::format_size(torrent.piece_size as u64).to_string()
+        ),
+        torrent.comment.clone(),
+        torrent.creator.clone(),
+        torrent.error_string.clone(),
+    ]
+}
+
+/// Format a unix timestamp from the daemon, which uses 0 for "never"
+pub fn format_date(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        return "—".to_string();
+    }
+
+    glib::DateTime::from_unix_local(timestamp)
+        .and_then(|date| date.format("%x %X"))
+        .map(|date| date.to_string())
+        .unwrap_or_default()
+}
+
+fn clear_list(list: &gtk::ListBox) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+}
+
+/// A two line row, with a title and a dimmed subtitle
+fn list_row(title: &str, subtitle: &str) -> gtk::ListBoxRow {
+    let title_label = gtk::Label::new(Some(title));
+    title_label.set_halign(gtk::Align::Start);
+    title_label.set_ellipsize(pango::EllipsizeMode::Middle);
+
+    let subtitle_label = gtk::Label::new(Some(subtitle));
+    subtitle_label.set_halign(gtk::Align::Start);
+    subtitle_label.add_css_class(granite::STYLE_CLASS_SMALL_LABEL);
+    subtitle_label.add_css_class("dim-label");
+
+    let row_box = gtk::Box::new(gtk::Orientation::Vertical, 3);
+    row_box.append(&title_label);
+    row_box.append(&subtitle_label);
+
+    let row = gtk::ListBoxRow::new();
+    row.set_activatable(false);
+    row.set_child(Some(&row_box));
+    row
+}
+
+/// The labels of a tracker row, kept so they can be refreshed without losing the selection
+struct TrackerRow {
+    row: gtk::ListBoxRow,
+    title: gtk::Label,
+    subtitle: gtk::Label,
+    status: gtk::Label,
+}
+
+impl TrackerRow {
+    fn new() -> Self {
+        let title = gtk::Label::new(None);
+        title.set_halign(gtk::Align::Start);
+        title.set_ellipsize(pango::EllipsizeMode::Middle);
+        title.set_selectable(true);
+
+        let subtitle = gtk::Label::new(None);
+        subtitle.set_halign(gtk::Align::Start);
+        subtitle.add_css_class(granite::STYLE_CLASS_SMALL_LABEL);
+        subtitle.add_css_class("dim-label");
+
+        let status = gtk::Label::new(None);
+        status.set_halign(gtk::Align::Start);
+        status.set_ellipsize(pango::EllipsizeMode::End);
+        status.add_css_class(granite::STYLE_CLASS_SMALL_LABEL);
+        status.add_css_class("dim-label");
+
+        let row_box = gtk::Box::new(gtk::Orientation::Vertical, 3);
+        row_box.append(&title);
+        row_box.append(&subtitle);
+        row_box.append(&status);
+
+        let row = gtk::ListBoxRow::new();
+        row.set_child(Some(&row_box));
+
+        Self {
+            row,
+            title,
+            subtitle,
+            status,
+        }
+    }
+}
+
+/// Transmission's `trackerList` format: one announce URL per line, with tiers separated by a
+/// blank line
+pub fn format_tracker_list(trackers: &[(i32, String)]) -> String {
+    let mut sorted = trackers.to_vec();
+    sorted.sort_by_key(|(tier, _)| *tier);
+
+    let mut list = String::new();
+    let mut last_tier = None;
+    for (tier, announce) in sorted {
+        if let Some(last_tier) = last_tier {
+            list.push_str(if last_tier == tier { "\n" } else { "\n\n" });
+        }
+        list.push_str(&announce);
+        last_tier = Some(tier);
+    }
+
+    list
+}
+
+fn is_valid_announce_url(url: &str) -> bool {
+    glib::Uri::parse(url, glib::UriFlags::NONE)
+        .map(|uri| matches!(uri.scheme().as_str(), "http" | "https" | "udp"))
+        .unwrap_or(false)
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for DetailsDialogModel {
+    type Init = ();
+    type Input = DetailsDialogInput;
+    type Output = DetailsDialogOutput;
+
+    view! {
+        #[root]
+        granite::Dialog {
+            #[watch]
+            set_visible: model.visible,
+            #[watch]
+            set_title: Some(&model.torrent_name),
+            set_default_size: (600, 450),
+            set_hide_on_close: true,
+
+            connect_close_request[sender] => move |_| {
+                sender.input(DetailsDialogInput::Close);
+                glib::Propagation::Stop
+            },
+
+            #[wrap(Some)]
+            set_child = &gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_margin_bottom: 12,
+                set_margin_start: 12,
+                set_margin_end: 12,
+                set_spacing: 6,
+
+                gtk::StackSwitcher {
+                    set_halign: gtk::Align::Center,
+                    set_stack: Some(&details_stack),
+                },
+
+                #[name = "details_stack"]
+                gtk::Stack {
+                    set_vexpand: true,
+                    #[watch]
+                    set_visible_child_name: model.page.name(),
+
+                    connect_visible_child_name_notify[sender] => move |stack| {
+                        if let Some(name) = stack.visible_child_name() {
+                            sender.input(DetailsDialogInput::PageChanged(name.to_string()));
+                        }
+                    },
+
+                    add_titled[Some(DetailsPage::General.name()), &fl!("details-general")] = &gtk::ScrolledWindow {
+                        #[name = "general_grid"]
+                        gtk::Grid {
+                            set_column_spacing: 12,
+                            set_row_spacing: 6,
+                            set_margin_top: 6,
+                        },
+                    },
+
+                    add_titled[Some(DetailsPage::Trackers.name()), &fl!("details-trackers")] = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 6,
+
+                        gtk::ScrolledWindow {
+                            set_vexpand: true,
+                            add_css_class: granite::STYLE_CLASS_FRAME,
+
+                            #[local_ref]
+                            trackers_list -> gtk::ListBox {
+                                set_selection_mode: gtk::SelectionMode::Single,
+
+                                connect_row_selected[sender] => move |_, row| {
+                                    sender.input(DetailsDialogInput::TrackerSelected(
+                                        row.map(|row| row.index() as usize),
+                                    ));
+                                },
+                            },
+                        },
+
+                        gtk::Label {
+                            set_halign: gtk::Align::Start,
+                            set_wrap: true,
+                            add_css_class: "error",
+                            #[watch]
+                            set_visible: model.tracker_error.is_some(),
+                            #[watch]
+                            set_label: model.tracker_error.as_deref().unwrap_or_default(),
+                        },
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 6,
+
+                            #[local_ref]
+                            tracker_entry -> gtk::Entry {
+                                set_hexpand: true,
+                                set_placeholder_text: Some(&fl!("tracker-url-placeholder")),
+                                set_input_purpose: gtk::InputPurpose::Url,
+
+                                connect_changed => move |entry| {
+                                    entry.remove_css_class("error");
+                                },
+                            },
+
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Horizontal,
+                                add_css_class: "linked",
+
+                                gtk::Button {
+                                    set_label: &fl!("action-add-tracker"),
+                                    connect_clicked => DetailsDialogInput::AddTracker,
+                                },
+
+                                gtk::Button {
+                                    set_label: &fl!("action-replace-tracker"),
+                                    #[watch]
+                                    set_sensitive: model.selected_tracker.is_some(),
+                                    connect_clicked => DetailsDialogInput::ReplaceTracker,
+                                },
+
+                                gtk::Button {
+                                    set_label: &fl!("action-remove-tracker"),
+                                    #[watch]
+                                    set_sensitive: model.selected_tracker.is_some(),
+                                    connect_clicked => DetailsDialogInput::RemoveTracker,
+                                },
+                            },
+
+                            gtk::Button {
+                                set_label: &fl!("action-reannounce"),
+                                set_tooltip_text: Some(&fl!("tooltip-reannounce")),
+                                connect_clicked => DetailsDialogInput::Reannounce,
+                            },
+                        },
+                    },
+
+                    add_titled[Some(DetailsPage::Peers.name()), &fl!("details-peers")] = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 6,
+
+                        gtk::ScrolledWindow {
+                            set_vexpand: true,
+                            add_css_class: granite::STYLE_CLASS_FRAME,
+
+                            #[local_ref]
+                            peers_view -> gtk::ColumnView {},
+                        },
+
+                        gtk::Label {
+                            set_halign: gtk::Align::Start,
+                            add_css_class: "dim-label",
+                            #[watch]
+                            set_label: &model.peers_summary,
+                        },
+                    },
+
+                    add_titled[Some(DetailsPage::Pieces.name()), &fl!("details-pieces")] = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 6,
+                        set_margin_top: 6,
+
+                        gtk::Label {
+                            set_halign: gtk::Align::Start,
+                            #[watch]
+                            set_label: &model.pieces_summary,
+                        },
+
+                        #[local_ref]
+                        piece_map -> PieceMap {
+                            set_hexpand: true,
+                            set_tooltip_text: Some(&fl!("details-piece-map-tooltip")),
+                        },
+                    },
+
+                    add_titled[Some(DetailsPage::Files.name()), &fl!("details-files")] = &gtk::ScrolledWindow {
+                        add_css_class: granite::STYLE_CLASS_FRAME,
+
+                        #[local_ref]
+                        files_list -> gtk::ListBox {
+                            set_selection_mode: gtk::SelectionMode::None,
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let mut model = DetailsDialogModel {
+            torrent_hash: String::new(),
+            torrent_id: 0,
+            torrent_name: String::new(),
+            page: DetailsPage::General,
+            general_values: vec![],
+            trackers_list: gtk::ListBox::new(),
+            tracker_rows: vec![],
+            trackers: vec![],
+            selected_tracker: None,
+            tracker_entry: gtk::Entry::new(),
+            tracker_error: None,
+            peers_view: PeersView::new(),
+            peers_summary: String::new(),
+            geoip: None,
+            geoip_path: String::new(),
+            settings: gio::Settings::new("com.github.davidmhewitt.torrential.settings"),
+            files_list: gtk::ListBox::new(),
+            pieces_summary: String::new(),
+            piece_map: PieceMap::new(),
+            visible: false,
+        };
+
+        let trackers_list = &model.trackers_list;
+        let tracker_entry = &model.tracker_entry;
+        let peers_view = &model.peers_view.column_view;
+        let files_list = &model.files_list;
+        let piece_map = &model.piece_map;
+
+        let widgets = view_output!();
+
+        for (row, heading) in general_headings().iter().enumerate() {
+            let heading_label = granite::HeaderLabel::new(heading);
+            heading_label.set_halign(gtk::Align::End);
+            heading_label.set_valign(gtk::Align::Start);
+            widgets
+                .general_grid
+                .attach(&heading_label, 0, row as i32, 1, 1);
+
+            let value_label = gtk::Label::new(None);
+            value_label.set_halign(gtk::Align::Start);
+            value_label.set_selectable(true);
+            value_label.set_wrap(true);
+            value_label.set_wrap_mode(pango::WrapMode::WordChar);
+            widgets
+                .general_grid
+                .attach(&value_label, 1, row as i32, 1, 1);
+
+            model.general_values.push(value_label);
+        }
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            DetailsDialogInput::Open(torrent_hash, torrent_id, torrent_name, page) => {
+                if self.torrent_hash != torrent_hash {
+                    clear_list(&self.trackers_list);
+                    self.tracker_rows.clear();
+                    self.trackers.clear();
+                    self.selected_tracker = None;
+                    self.tracker_entry.set_text("");
+                    self.tracker_error = None;
+                    self.peers_view.clear();
+                    self.peers_summary.clear();
+                    clear_list(&self.files_list);
+                    self.pieces_summary.clear();
+                    self.piece_map.clear();
+                    for label in &self.general_values {
+                        label.set_text("");
+                    }
+                }
+
+                self.torrent_hash = torrent_hash;
+                self.torrent_id = torrent_id;
+                self.torrent_name = torrent_name;
+                self.page = page;
+                self.visible = true;
+                self.request_page(&sender);
+            }
+            DetailsDialogInput::Close => {
+                self.visible = false;
+            }
+            DetailsDialogInput::Refresh => {
+                // Only poll the daemon for details while they're on screen
+                if self.visible {
+                    self.request_page(&sender);
+                }
+            }
+            DetailsDialogInput::PageChanged(name) => {
+                let page = DetailsPage::from_name(&name);
+                if page != self.page {
+                    self.page = page;
+                    if self.visible {
+                        self.request_page(&sender);
+                    }
+                }
+            }
+            DetailsDialogInput::GeneralChanged(torrent) => {
+                if torrent.id != self.torrent_id {
+                    return;
+                }
+
+                self.torrent_name.clone_from(&torrent.name);
+                for (label, value) in self.general_values.iter().zip(general_values(&torrent)) {
+                    label.set_text(&value);
+                }
+            }
+            DetailsDialogInput::TrackersChanged(trackers) => {
+                if trackers.id != self.torrent_id {
+                    return;
+                }
+
+                let entries: Vec<(i32, String)> = trackers
+                    .tracker_stats
+                    .iter()
+                    .map(|tracker| (tracker.tier, tracker.announce.clone()))
+                    .collect();
+
+                // Only rebuild the rows when the trackers themselves change, so that the
+                // periodic refresh doesn't reset the selection
+                if entries != self.trackers {
+                    clear_list(&self.trackers_list);
+                    self.tracker_rows = entries
+                        .iter()
+                        .map(|_| {
+                            let row = TrackerRow::new();
+                            self.trackers_list.append(&row.row);
+                            row
+                        })
+                        .collect();
+                    self.trackers = entries;
+                }
+
+                for (row, tracker) in self.tracker_rows.iter().zip(&trackers.tracker_stats) {
+                    row.title.set_text(&tracker.announce);
+                    row.subtitle.set_text(&fl!(
+                        "details-tracker-subtitle",
+                        tier = tracker.tier + 1,
+                        result = tracker.last_announce_result.clone(),
+                        seeders = tracker.seeder_count,
+                        leechers = tracker.leecher_count
+                    ));
+
+                    let scrape = if tracker.last_scrape_result.is_empty() {
+                        "—".to_string()
+                    } else {
+                        tracker.last_scrape_result.clone()
+                    };
+                    row.status.set_text(&fl!(
+                        "details-tracker-status",
+                        next = format_date(tracker.next_announce_time),
+                        scrape = scrape
+                    ));
+                }
+            }
+            DetailsDialogInput::PeersChanged(peers) => {
+                if peers.id != self.torrent_id {
+                    return;
+                }
+
+                self.load_geoip_database(&sender);
+                self.peers_view.set_peers(&peers.peers, self.geoip.as_ref());
+
+                let from = &peers.peers_from;
+                self.peers_summary = fl!(
+                    "details-peers-from",
+                    tracker = from.from_tracker,
+                    dht = from.from_dht,
+                    pex = from.from_pex,
+                    lpd = from.from_lpd,
+                    incoming = from.from_incoming
+                );
+            }
+            DetailsDialogInput::GeoIpLoaded(path, geoip) => {
+                // Drop a database that was replaced in the settings while it loaded
+                if path == self.geoip_path {
+                    self.geoip = geoip;
+                }
+            }
+            DetailsDialogInput::PiecesChanged(pieces) => {
+                if pieces.id != self.torrent_id {
+                    return;
+                }
+
+                self.pieces_summary = fl!(
+                    "details-pieces-value",
+                    count = pieces.piece_count,
+                    size = glib::format_size(pieces.piece_size as u64).to_string()
+                );
+
+                // Per piece peer counts are only reported by Transmission 4 and later
+                self.piece_map.set_pieces(
+                    decode_pieces(&pieces.pieces, pieces.piece_count as usize),
+                    pieces.availability.unwrap_or_default(),
+                );
+            }
+            DetailsDialogInput::FilesChanged(files) => {
+                if files.id != self.torrent_id {
+                    return;
+                }
+
+                clear_list(&self.files_list);
+                for file in &files.files {
+                    let progress = if file.length > 0 {
+                        file.bytes_completed as f64 / file.length as f64
+                    } else {
+                        0.0
+                    };
+                    let subtitle = format!(
+                        "{} / {} ({:.0}%)",
+                        glib::format_size(file.bytes_completed as u64),
+                        glib::format_size(file.length as u64),
+                        progress * 100.0
+                    );
+                    self.files_list.append(&list_row(&file.name, &subtitle));
+                }
+            }
+            DetailsDialogInput::TrackerSelected(index) => {
+                self.selected_tracker = index;
+                if let Some((_, announce)) = index.and_then(|index| self.trackers.get(index)) {
+                    self.tracker_entry.set_text(announce);
+                }
+            }
+            DetailsDialogInput::AddTracker => {
+                let Some(announce) = self.entered_tracker_url() else {
+                    return;
+                };
+
+                // New trackers get a tier of their own, after the existing ones
+                let tier = self
+                    .trackers
+                    .iter()
+                    .map(|(tier, _)| tier + 1)
+                    .max()
+                    .unwrap_or(0);
+
+                let mut trackers = self.trackers.clone();
+                trackers.push((tier, announce));
+                self.set_tracker_list(&trackers, &sender);
+            }
+            DetailsDialogInput::ReplaceTracker => {
+                let Some(index) = self.selected_tracker else {
+                    return;
+                };
+                let Some(announce) = self.entered_tracker_url() else {
+                    return;
+                };
+
+                let mut trackers = self.trackers.clone();
+                if let Some(tracker) = trackers.get_mut(index) {
+                    tracker.1 = announce;
+                }
+                self.set_tracker_list(&trackers, &sender);
+            }
+            DetailsDialogInput::RemoveTracker => {
+                let Some(index) = self.selected_tracker else {
+                    return;
+                };
+
+                let mut trackers = self.trackers.clone();
+                if index < trackers.len() {
+                    trackers.remove(index);
+                }
+                self.set_tracker_list(&trackers, &sender);
+            }
+            DetailsDialogInput::TrackerEditFailed(err) => {
+                self.tracker_error = Some(fl!("details-tracker-edit-failed", error = err));
+            }
+            DetailsDialogInput::Reannounce => {
+                sender
+                    .output(DetailsDialogOutput::Reannounce(self.torrent_hash.clone()))
+                    .ok();
+            }
+        }
+    }
+}
+
+impl DetailsDialogModel {
+    /// (Re)load the country database in the background if the configured file has changed
+    fn load_geoip_database(&mut self, sender: &ComponentSender<Self>) {
+        let path = self.settings.string("geoip-database").to_string();
+        if path == self.geoip_path {
+            return;
+        }
+
+        self.geoip = None;
+        self.geoip_path.clone_from(&path);
+        if path.is_empty() {
+            return;
+        }
+
+        let sender = sender.clone();
+        relm4::spawn_blocking(move || {
+            let geoip = match GeoIpDatabase::load(Path::new(&path)) {
+                Ok(geoip) => Some(geoip),
+                Err(err) => {
+                    log::error!("Error loading GeoIP database {}: {}", path, err);
+                    None
+                }
+            };
+            sender.input(DetailsDialogInput::GeoIpLoaded(path, geoip));
+        });
+    }
+
+    /// The URL typed into the tracker entry, or `None` after flagging the entry if it isn't a
+    /// usable announce URL
+    fn entered_tracker_url(&self) -> Option<String> {
+        let url = self.tracker_entry.text().trim().to_string();
+        let duplicate = self.trackers.iter().any(|(_, announce)| *announce == url);
+
+        if !is_valid_announce_url(&url) || duplicate {
+            self.tracker_entry.add_css_class("error");
+            return None;
+        }
+
+        Some(url)
+    }
+
+    fn set_tracker_list(&mut self, trackers: &[(i32, String)], sender: &ComponentSender<Self>) {
+        self.tracker_entry.set_text("");
+        self.tracker_error = None;
+        self.selected_tracker = None;
+
+        sender
+            .output(DetailsDialogOutput::SetTrackerList(
+                self.torrent_hash.clone(),
+                self.torrent_id,
+                format_tracker_list(trackers),
+            ))
+            .ok();
+    }
+
+    /// Ask for the general details, plus whatever the visible page shows
+    fn request_page(&self, sender: &ComponentSender<Self>) {
+        let id = self.torrent_id;
+        sender.output(DetailsDialogOutput::GetGeneral(id)).ok();
+
+        let request = match self.page {
+            DetailsPage::General => return,
+            DetailsPage::Trackers => DetailsDialogOutput::GetTrackers(id),
+            DetailsPage::Peers => DetailsDialogOutput::GetPeers(id),
+            DetailsPage::Pieces => DetailsDialogOutput::GetPieces(id),
+            DetailsPage::Files => DetailsDialogOutput::GetFiles(id),
+        };
+        sender.output(request).ok();
+    }
+}
@@ -1,17 +1,142 @@
-use crate::fl;
+use crate::{fl, utils};
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{gio, glib};
 use relm4::{gtk, ComponentParts, ComponentSender, SimpleComponent};
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use transmission_client::TorrentFiles;
 
 struct ItemBindings {
-    handler_id: glib::SignalHandlerId,
-    wanted_binding: glib::Binding,
-    inconsistent_binding: glib::Binding,
+    bindings: Vec<glib::Binding>,
+    handlers: Vec<(glib::Object, glib::SignalHandlerId)>,
+}
+
+/// Transmission's per-file priority values (`TR_PRI_LOW`, `TR_PRI_NORMAL`, `TR_PRI_HIGH`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilePriority {
+    Low = -1,
+    Normal = 0,
+    High = 1,
+}
+
+impl FilePriority {
+    const ALL: [FilePriority; 3] = [FilePriority::High, FilePriority::Normal, FilePriority::Low];
+
+    fn label(&self) -> String {
+        match self {
+            FilePriority::Low => fl!("priority-low"),
+            FilePriority::Normal => fl!("priority-normal"),
+            FilePriority::High => fl!("priority-high"),
+        }
+    }
+}
+
+impl From<i32> for FilePriority {
+    fn from(value: i32) -> Self {
+        match value {
+            i32::MIN..=-1 => FilePriority::Low,
+            0 => FilePriority::Normal,
+            _ => FilePriority::High,
+        }
+    }
+}
+
+/// A group of files that can be selected in one go from the "Select by Type" menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileTypeFilter {
+    MediaType(String),
+    Extension(String),
+}
+
+impl FileTypeFilter {
+    fn label(&self) -> String {
+        match self {
+            FileTypeFilter::MediaType(media_type) => match media_type.as_str() {
+                "video" => fl!("file-type-video"),
+                "audio" => fl!("file-type-audio"),
+                "image" => fl!("file-type-image"),
+                "text" => fl!("file-type-text"),
+                other => other.to_string(),
+            },
+            FileTypeFilter::Extension(extension) => extension.clone(),
+        }
+    }
+
+    fn matches(&self, file_node: &FileNode) -> bool {
+        match self {
+            FileTypeFilter::MediaType(media_type) => {
+                file_node.media_type().as_ref() == Some(media_type)
+            }
+            FileTypeFilter::Extension(extension) => {
+                file_node.extension().as_ref() == Some(extension)
+            }
+        }
+    }
+}
+
+/// The user-editable state of a single file in the torrent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileState {
+    pub wanted: bool,
+    pub priority: FilePriority,
+}
+
+/// A file from the torrent, flattened out of Transmission's `files` and `fileStats` lists
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub name: String,
+    pub length: i64,
+    pub bytes_completed: i64,
+    pub state: FileState,
+}
+
+/// The changes to send to Transmission when the file selection is applied
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FileSelection {
+    pub wanted: Vec<i32>,
+    pub unwanted: Vec<i32>,
+    pub priority_high: Vec<i32>,
+    pub priority_normal: Vec<i32>,
+    pub priority_low: Vec<i32>,
+}
+
+impl FileSelection {
+    pub fn is_empty(&self) -> bool {
+        self.wanted.is_empty()
+            && self.unwanted.is_empty()
+            && self.priority_high.is_empty()
+            && self.priority_normal.is_empty()
+            && self.priority_low.is_empty()
+    }
+}
+
+/// Work out which files changed between two sets of states, indexed by file index
+pub fn diff_file_states(original: &[FileState], current: &[FileState]) -> FileSelection {
+    let mut selection = FileSelection::default();
+
+    for (index, (before, after)) in original.iter().zip(current).enumerate() {
+        let index = index as i32;
+
+        if before.wanted != after.wanted {
+            if after.wanted {
+                selection.wanted.push(index);
+            } else {
+                selection.unwanted.push(index);
+            }
+        }
+
+        if before.priority != after.priority {
+            match after.priority {
+                FilePriority::High => selection.priority_high.push(index),
+                FilePriority::Normal => selection.priority_normal.push(index),
+                FilePriority::Low => selection.priority_low.push(index),
+            }
+        }
+    }
+
+    selection
 }
 
 mod imp {
@@ -24,6 +149,10 @@ mod imp {
         pub index: Cell<i32>, // -1 for folders
         pub wanted: Cell<bool>,
         pub inconsistent: Cell<bool>,
+        pub priority: Cell<i32>,
+        pub priority_mixed: Cell<bool>,
+        pub size: Cell<i64>,
+        pub bytes_completed: Cell<i64>,
         pub children: RefCell<Vec<super::FileNode>>,
     }
 
@@ -50,6 +179,14 @@ mod imp {
                     glib::ParamSpecBoolean::builder("inconsistent")
                         .read_only()
                         .build(),
+                    glib::ParamSpecInt::builder("priority")
+                        .minimum(-1)
+                        .maximum(1)
+                        .readwrite()
+                        .build(),
+                    glib::ParamSpecString::builder("priority-label")
+                        .read_only()
+                        .build(),
                 ]
             })
         }
@@ -61,6 +198,8 @@ mod imp {
                 "icon-name" => obj.icon_name().to_value(),
                 "wanted" => obj.wanted().to_value(),
                 "inconsistent" => obj.inconsistent().to_value(),
+                "priority" => (obj.priority() as i32).to_value(),
+                "priority-label" => obj.priority_label().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -71,6 +210,10 @@ mod imp {
                     let wanted = value.get().expect("Expected bool");
                     self.wanted.set(wanted);
                 }
+                "priority" => {
+                    let priority = value.get().expect("Expected i32");
+                    self.priority.set(priority);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -82,14 +225,16 @@ glib::wrapper! {
 }
 
 impl FileNode {
-    pub fn new_file(name: &str, path: &str, index: usize, wanted: bool) -> Self {
+    /// The file's path is set when it's placed in the tree
+    pub fn new_file(name: &str, index: usize, wanted: bool, priority: FilePriority) -> Self {
         let obj: Self = glib::Object::new();
         let imp = obj.imp();
         imp.name.replace(name.to_string());
-        imp.path.replace(path.to_string());
         imp.index.set(index as i32);
         imp.wanted.set(wanted);
         imp.inconsistent.set(false);
+        imp.priority.set(priority as i32);
+        imp.priority_mixed.set(false);
         obj
     }
 
@@ -101,6 +246,8 @@ impl FileNode {
         imp.index.set(-1);
         imp.wanted.set(true);
         imp.inconsistent.set(false);
+        imp.priority.set(FilePriority::Normal as i32);
+        imp.priority_mixed.set(false);
         obj
     }
 
@@ -138,6 +285,60 @@ impl FileNode {
         self.notify("inconsistent");
     }
 
+    pub fn priority(&self) -> FilePriority {
+        self.imp().priority.get().into()
+    }
+
+    pub fn set_priority(&self, priority: FilePriority) {
+        self.imp().priority.set(priority as i32);
+        self.notify("priority");
+        self.notify("priority-label");
+    }
+
+    /// Whether this folder contains children with differing priorities
+    pub fn priority_mixed(&self) -> bool {
+        self.imp().priority_mixed.get()
+    }
+
+    pub fn set_priority_mixed(&self, mixed: bool) {
+        self.imp().priority_mixed.set(mixed);
+        self.notify("priority-label");
+    }
+
+    /// Total size in bytes, summed over all descendants for folders
+    pub fn size(&self) -> i64 {
+        self.imp().size.get()
+    }
+
+    pub fn bytes_completed(&self) -> i64 {
+        self.imp().bytes_completed.get()
+    }
+
+    pub fn set_sizes(&self, size: i64, bytes_completed: i64) {
+        self.imp().size.set(size);
+        self.imp().bytes_completed.set(bytes_completed);
+    }
+
+    pub fn progress(&self) -> f64 {
+        if self.size() > 0 {
+            self.bytes_completed() as f64 / self.size() as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.size() > 0 && self.bytes_completed() >= self.size()
+    }
+
+    pub fn priority_label(&self) -> String {
+        if self.priority_mixed() {
+            fl!("priority-mixed")
+        } else {
+            self.priority().label()
+        }
+    }
+
     pub fn icon_name(&self) -> String {
         if self.is_folder() {
             gio::content_type_get_generic_icon_name("inode/directory")
@@ -210,6 +411,22 @@ impl FileNode {
         let inconsistent = !all_wanted && any_wanted;
         self.set_inconsistent(inconsistent);
         self.set_wanted(any_wanted);
+
+        // A folder only has a single priority if every descendant shares it
+        let first_priority = children[0].priority();
+        let mixed = children
+            .iter()
+            .any(|child| child.priority_mixed() || child.priority() != first_priority);
+
+        if !mixed {
+            self.set_priority(first_priority);
+        }
+        self.set_priority_mixed(mixed);
+
+        self.set_sizes(
+            children.iter().map(|child| child.size()).sum(),
+            children.iter().map(|child| child.bytes_completed()).sum(),
+        );
     }
 
     /// Set wanted state for this node and all descendants
@@ -221,31 +438,127 @@ impl FileNode {
             child.set_wanted_recursive(wanted);
         }
     }
+
+    /// All files below this node, or the node itself if it is a file
+    pub fn files(&self) -> Vec<FileNode> {
+        if !self.is_folder() {
+            return vec![self.clone()];
+        }
+
+        self.children()
+            .iter()
+            .flat_map(|child| child.files())
+            .collect()
+    }
+
+    /// The nodes below this one to show for a (lowercase) search term: those with a name
+    /// containing it, the folders leading to them, and everything inside a matching folder
+    pub fn search_results(&self, search_term_lower: &str) -> HashSet<FileNode> {
+        let mut results = HashSet::new();
+        self.collect_search_results(search_term_lower, false, &mut results);
+        results
+    }
+
+    /// Add the nodes below this one to show for the search to `results`, returning whether
+    /// there were any
+    fn collect_search_results(
+        &self,
+        search_term_lower: &str,
+        ancestor_matched: bool,
+        results: &mut HashSet<FileNode>,
+    ) -> bool {
+        let mut found = false;
+        for child in self.children() {
+            let matched =
+                ancestor_matched || child.name().to_lowercase().contains(search_term_lower);
+            let below = child.collect_search_results(search_term_lower, matched, results);
+            if matched || below {
+                results.insert(child);
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// Lowercase file extension including the leading dot, e.g. `.flac`
+    pub fn extension(&self) -> Option<String> {
+        if self.is_folder() {
+            return None;
+        }
+
+        std::path::Path::new(&self.name())
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy().to_lowercase()))
+    }
+
+    /// The top level media type of this file's guessed content type, e.g. `video`
+    pub fn media_type(&self) -> Option<String> {
+        if self.is_folder() {
+            return None;
+        }
+
+        let name = self.name();
+        let (content_type, _) = gio::content_type_guess(Some(name.as_str()), None::<&[u8]>);
+        gio::content_type_get_mime_type(&content_type)
+            .and_then(|mime| mime.split('/').next().map(|s| s.to_string()))
+    }
+
+    /// Set priority for this node and all descendants
+    pub fn set_priority_recursive(&self, priority: FilePriority) {
+        self.set_priority(priority);
+        self.set_priority_mixed(false);
+
+        for child in self.children() {
+            child.set_priority_recursive(priority);
+        }
+    }
 }
 
 pub struct FileSelectDialogModel {
     torrent_hash: String,
     torrent_id: i32,
     torrent_name: String,
+    download_dir: String,
     files: TorrentFiles,
+    original_states: Vec<FileState>,
     root_store: gio::ListStore,
     tree_model: Option<gtk::TreeListModel>,
-    list_view: Option<gtk::ListView>,
+    row_sorter: Option<gtk::TreeListRowSorter>,
+    column_view: Option<gtk::ColumnView>,
+    type_box: gtk::Box,
+    search_term: String,
+    summary: String,
+    rename_error: Option<String>,
     visible: bool,
 }
 
 #[derive(Debug)]
 pub enum FileSelectDialogInput {
-    Open(String, i32, String, TorrentFiles),
-    Close,
+    Open(String, i32, String, String, TorrentFiles),
+    FilesChanged(String, TorrentFiles),
+    Apply,
+    Cancel,
     ToggleFile(FileNode, gtk::TreeListRow, bool),
+    SetPriority(FileNode, gtk::TreeListRow, FilePriority),
+    OpenFile(FileNode),
+    ShowFileInFolder(FileNode),
+    Search(String),
+    SelectAll,
+    SelectNone,
+    InvertSelection,
+    SelectByType(FileTypeFilter),
+    Rename(FileNode, gtk::TreeListRow, String),
+    RenameFailed(i32, String), // torrent_id, error
 }
 
 #[derive(Debug)]
 pub enum FileSelectDialogOutput {
-    UpdateFiles(String, i32, Vec<i32>, Vec<i32>), // torrent_hash, torrent_id, wanted, unwanted
+    UpdateFiles(String, i32, FileSelection), // torrent_hash, torrent_id, selection
+    RenamePath(String, i32, String, String), // torrent_hash, torrent_id, path, new_name
 }
 
+type BindingsStorage = Rc<RefCell<HashMap<usize, ItemBindings>>>;
+
 #[relm4::component(pub)]
 impl SimpleComponent for FileSelectDialogModel {
     type Init = ();
@@ -257,14 +570,14 @@ impl SimpleComponent for FileSelectDialogModel {
         dialog = granite::Dialog {
             set_title: Some("Select Files to Download"),
             set_modal: true,
-            set_default_size: (550, 400),
+            set_default_size: (750, 450),
             set_hide_on_close: true,
 
             #[watch]
             set_visible: model.visible,
 
             connect_close_request[sender] => move |_| {
-                sender.input(FileSelectDialogInput::Close);
+                sender.input(FileSelectDialogInput::Cancel);
                 glib::Propagation::Stop
             },
 
@@ -277,25 +590,86 @@ impl SimpleComponent for FileSelectDialogModel {
                 set_margin_end: 12,
                 set_spacing: 6,
 
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 6,
+
+                    gtk::SearchEntry {
+                        set_hexpand: true,
+                        set_placeholder_text: Some(&fl!("file-search-placeholder")),
+                        connect_search_changed[sender] => move |search_entry| {
+                            sender.input(FileSelectDialogInput::Search(search_entry.text().to_string()));
+                        },
+                    },
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        add_css_class: "linked",
+
+                        gtk::Button {
+                            set_label: &fl!("action-select-all"),
+                            connect_clicked => FileSelectDialogInput::SelectAll,
+                        },
+
+                        gtk::Button {
+                            set_label: &fl!("action-select-none"),
+                            connect_clicked => FileSelectDialogInput::SelectNone,
+                        },
+
+                        gtk::Button {
+                            set_label: &fl!("action-invert-selection"),
+                            connect_clicked => FileSelectDialogInput::InvertSelection,
+                        },
+
+                        #[name(type_menu_button)]
+                        gtk::MenuButton {
+                            set_label: &fl!("action-select-by-type"),
+                        },
+                    },
+                },
+
+                gtk::Label {
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "error",
+                    #[watch]
+                    set_visible: model.rename_error.is_some(),
+                    #[watch]
+                    set_label: model.rename_error.as_deref().unwrap_or_default(),
+                },
+
                 gtk::ScrolledWindow {
                     set_vexpand: true,
                     set_hexpand: true,
                     add_css_class: granite::STYLE_CLASS_FRAME,
 
-                    #[name(list_view)]
-                    gtk::ListView {
+                    #[name(column_view)]
+                    gtk::ColumnView {
                         set_vexpand: true,
+                        set_show_column_separators: true,
                     }
                 },
 
                 gtk::Box {
                     set_orientation: gtk::Orientation::Horizontal,
-                    set_halign: gtk::Align::End,
                     set_spacing: 6,
 
+                    gtk::Label {
+                        set_hexpand: true,
+                        set_halign: gtk::Align::Start,
+                        add_css_class: "dim-label",
+                        #[watch]
+                        set_label: &model.summary,
+                    },
+
                     gtk::Button {
-                        set_label: &fl!("action-close"),
-                        connect_clicked => FileSelectDialogInput::Close,
+                        set_label: &fl!("action-cancel"),
+                        connect_clicked => FileSelectDialogInput::Cancel,
+                    },
+
+                    gtk::Button {
+                        set_label: &fl!("action-apply"),
+                        add_css_class: "suggested-action",
+                        connect_clicked => FileSelectDialogInput::Apply,
                     }
                 }
             }
@@ -313,6 +687,7 @@ impl SimpleComponent for FileSelectDialogModel {
             torrent_hash: String::new(),
             torrent_id: 0,
             torrent_name: String::new(),
+            download_dir: String::new(),
             files: TorrentFiles {
                 id: 0,
                 file_count: 0,
@@ -321,19 +696,248 @@ impl SimpleComponent for FileSelectDialogModel {
                 wanted: vec![],
                 priorities: vec![],
             },
+            original_states: vec![],
             root_store: root_store.clone(),
             tree_model: None,
-            list_view: None,
+            row_sorter: None,
+            column_view: None,
+            type_box: gtk::Box::new(gtk::Orientation::Vertical, 0),
+            search_term: String::new(),
+            summary: String::new(),
+            rename_error: None,
             visible: false,
         };
 
         let widgets = view_output!();
 
-        let factory = gtk::SignalListItemFactory::new();
+        // Filled with one entry per file type whenever a torrent is opened
+        let type_popover = gtk::Popover::new();
+        type_popover.set_child(Some(&model.type_box));
+        widgets.type_menu_button.set_popover(Some(&type_popover));
 
         // Shared storage for bindings, keyed by ListItem pointer address (stable identity)
-        let bindings_storage: Rc<RefCell<HashMap<usize, ItemBindings>>> =
-            Rc::new(RefCell::new(HashMap::new()));
+        let bindings_storage: BindingsStorage = Rc::new(RefCell::new(HashMap::new()));
+
+        let name_column = gtk::ColumnViewColumn::new(
+            Some(&fl!("file-column-name")),
+            Some(Self::name_factory(&sender, &bindings_storage)),
+        );
+        name_column.set_expand(true);
+        name_column.set_resizable(true);
+        name_column.set_sorter(Some(&file_node_sorter(|a, b| {
+            match (a.is_folder(), b.is_folder()) {
+                (true, false) => std::cmp::Ordering::Less,
+                (false, true) => std::cmp::Ordering::Greater,
+                _ => a.name().to_lowercase().cmp(&b.name().to_lowercase()),
+            }
+        })));
+
+        let size_column =
+            gtk::ColumnViewColumn::new(Some(&fl!("file-column-size")), Some(Self::size_factory()));
+        size_column.set_sorter(Some(&file_node_sorter(|a, b| a.size().cmp(&b.size()))));
+
+        let progress_column = gtk::ColumnViewColumn::new(
+            Some(&fl!("file-column-progress")),
+            Some(Self::progress_factory()),
+        );
+        progress_column.set_fixed_width(120);
+        progress_column.set_sorter(Some(&file_node_sorter(|a, b| {
+            a.progress().total_cmp(&b.progress())
+        })));
+
+        let priority_column = gtk::ColumnViewColumn::new(
+            Some(&fl!("file-column-priority")),
+            Some(Self::priority_factory(&sender, &bindings_storage)),
+        );
+        priority_column.set_sorter(Some(&file_node_sorter(|a, b| {
+            (a.priority() as i32).cmp(&(b.priority() as i32))
+        })));
+
+        let actions_column = gtk::ColumnViewColumn::new(
+            None,
+            Some(Self::actions_factory(&sender, &bindings_storage)),
+        );
+
+        widgets.column_view.append_column(&name_column);
+        widgets.column_view.append_column(&size_column);
+        widgets.column_view.append_column(&progress_column);
+        widgets.column_view.append_column(&priority_column);
+        widgets.column_view.append_column(&actions_column);
+        widgets
+            .column_view
+            .sort_by_column(Some(&name_column), gtk::SortType::Ascending);
+
+        // Sort within each folder while keeping the tree structure intact
+        let row_sorter = gtk::TreeListRowSorter::new(widgets.column_view.sorter());
+
+        // Store the column view reference in the model
+        let mut model = model;
+        model.row_sorter = Some(row_sorter);
+        model.column_view = Some(widgets.column_view.clone());
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            FileSelectDialogInput::Open(
+                torrent_hash,
+                torrent_id,
+                torrent_name,
+                download_dir,
+                files,
+            ) => {
+                self.torrent_hash = torrent_hash;
+                self.torrent_id = torrent_id;
+                self.torrent_name = torrent_name.clone();
+                self.download_dir = download_dir;
+                self.files = files;
+                self.rename_error = None;
+                self.populate_tree();
+                self.populate_type_menu(&sender);
+                self.update_summary();
+                self.visible = true;
+            }
+            FileSelectDialogInput::FilesChanged(torrent_name, files) => {
+                if self.visible && files.id == self.torrent_id {
+                    // Rebuild with the new names, but keep any changes not yet applied
+                    let original_states = self.original_states.clone();
+                    let current_states = self.current_states();
+
+                    self.torrent_name = torrent_name;
+                    self.files = files;
+                    self.populate_tree();
+                    self.restore_pending_states(&original_states, &current_states);
+                    self.populate_type_menu(&sender);
+                    self.update_summary();
+                }
+            }
+            FileSelectDialogInput::Apply => {
+                self.visible = false;
+                // Only send the files whose wanted state or priority actually changed
+                let selection = diff_file_states(&self.original_states, &self.current_states());
+                if !selection.is_empty() {
+                    sender
+                        .output(FileSelectDialogOutput::UpdateFiles(
+                            self.torrent_hash.clone(),
+                            self.torrent_id,
+                            selection,
+                        ))
+                        .ok();
+                }
+            }
+            FileSelectDialogInput::Cancel => {
+                // The tree is rebuilt from the torrent's files on the next open
+                self.visible = false;
+            }
+            FileSelectDialogInput::ToggleFile(file_node, tree_row, new_state) => {
+                self.toggle_file(&file_node, &tree_row, new_state);
+                self.update_summary();
+            }
+            FileSelectDialogInput::SetPriority(file_node, tree_row, priority) => {
+                self.set_file_priority(&file_node, &tree_row, priority);
+            }
+            FileSelectDialogInput::OpenFile(file_node) => {
+                utils::open_torrent_file(&self.download_dir, &file_node.path());
+            }
+            FileSelectDialogInput::ShowFileInFolder(file_node) => {
+                utils::open_torrent_location(&self.download_dir, &file_node.path());
+            }
+            FileSelectDialogInput::Search(search_term) => {
+                self.search_term = search_term;
+                self.refresh_tree_model();
+            }
+            FileSelectDialogInput::SelectAll => {
+                self.set_matching_files_wanted(|_| true);
+            }
+            FileSelectDialogInput::SelectNone => {
+                self.set_matching_files_wanted(|_| false);
+            }
+            FileSelectDialogInput::InvertSelection => {
+                self.set_matching_files_wanted(|file_node| !file_node.wanted());
+            }
+            FileSelectDialogInput::SelectByType(type_filter) => {
+                self.set_matching_files_wanted(|file_node| type_filter.matches(file_node));
+            }
+            FileSelectDialogInput::Rename(file_node, tree_row, new_name) => {
+                let siblings: Vec<String> = tree_row
+                    .parent()
+                    .and_then(|parent| parent.item().and_downcast::<FileNode>())
+                    .map(|parent| parent.children())
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|sibling| *sibling != &file_node)
+                    .map(|sibling| sibling.name())
+                    .collect();
+
+                match utils::validate_new_name(&new_name, &siblings) {
+                    Ok(_) => {
+                        self.rename_error = None;
+                        sender
+                            .output(FileSelectDialogOutput::RenamePath(
+                                self.torrent_hash.clone(),
+                                self.torrent_id,
+                                file_node.path(),
+                                new_name.clone(),
+                            ))
+                            .ok();
+                        // Keep the old name, and with it the old paths, until the daemon has
+                        // renamed it and the tree is rebuilt from the new file list
+                        file_node.notify("name");
+                    }
+                    Err(err) => {
+                        self.rename_error = Some(err.message());
+                        // Put the old name back into the label
+                        file_node.notify("name");
+                    }
+                }
+            }
+            FileSelectDialogInput::RenameFailed(torrent_id, err) => {
+                if self.visible && torrent_id == self.torrent_id {
+                    self.rename_error = Some(fl!("rename-error-failed", error = err));
+                }
+            }
+        }
+    }
+}
+
+/// Wraps a comparison of two `FileNode`s in a sorter usable by a `gtk::TreeListRowSorter`
+fn file_node_sorter<F>(compare: F) -> gtk::CustomSorter
+where
+    F: Fn(&FileNode, &FileNode) -> std::cmp::Ordering + 'static,
+{
+    gtk::CustomSorter::new(move |a, b| {
+        let a = a.downcast_ref::<FileNode>().expect("Needs to be FileNode");
+        let b = b.downcast_ref::<FileNode>().expect("Needs to be FileNode");
+        compare(a, b).into()
+    })
+}
+
+/// Get the ListItem and the tree row and node it currently displays
+fn bound_item(list_item: &glib::Object) -> (&gtk::ListItem, gtk::TreeListRow, FileNode) {
+    let list_item = list_item
+        .downcast_ref::<gtk::ListItem>()
+        .expect("Needs to be ListItem");
+
+    let tree_list_row = list_item
+        .item()
+        .and_downcast::<gtk::TreeListRow>()
+        .expect("Needs to be TreeListRow");
+
+    let file_node = tree_list_row
+        .item()
+        .and_downcast::<FileNode>()
+        .expect("Needs to be FileNode");
+
+    (list_item, tree_list_row, file_node)
+}
+
+impl FileSelectDialogModel {
+    fn name_factory(
+        sender: &ComponentSender<Self>,
+        bindings_storage: &BindingsStorage,
+    ) -> gtk::SignalListItemFactory {
+        let factory = gtk::SignalListItemFactory::new();
 
         factory.connect_setup(|_, list_item| {
             let list_item = list_item
@@ -349,8 +953,9 @@ impl SimpleComponent for FileSelectDialogModel {
             let icon = gtk::Image::new();
             content_box.append(&icon);
 
-            let label = gtk::Label::new(None);
+            let label = gtk::EditableLabel::new("");
             label.set_halign(gtk::Align::Start);
+            label.set_tooltip_text(Some(&fl!("file-rename-tooltip")));
             content_box.append(&label);
 
             expander.set_child(Some(&content_box));
@@ -360,23 +965,11 @@ impl SimpleComponent for FileSelectDialogModel {
         let sender_clone = sender.clone();
         let bindings_for_bind = bindings_storage.clone();
         factory.connect_bind(move |_, list_item| {
-            let list_item = list_item
-                .downcast_ref::<gtk::ListItem>()
-                .expect("Needs to be ListItem");
+            let (list_item, tree_list_row, file_node) = bound_item(list_item);
 
             // Use pointer address as stable key for this ListItem instance
             let key = list_item.as_ptr() as usize;
 
-            let tree_list_row = list_item
-                .item()
-                .and_downcast::<gtk::TreeListRow>()
-                .expect("Needs to be TreeListRow");
-
-            let file_node = tree_list_row
-                .item()
-                .and_downcast::<FileNode>()
-                .expect("Needs to be FileNode");
-
             let expander = list_item
                 .child()
                 .and_downcast::<gtk::TreeExpander>()
@@ -401,13 +994,20 @@ impl SimpleComponent for FileSelectDialogModel {
 
             let label = icon
                 .next_sibling()
-                .and_downcast::<gtk::Label>()
-                .expect("Needs to be Label");
+                .and_downcast::<gtk::EditableLabel>()
+                .expect("Needs to be EditableLabel");
 
             // Bind properties
-            label.set_text(&file_node.name());
             icon.set_icon_name(Some(&file_node.icon_name()));
 
+            // The root node stands for the whole torrent, which is renamed from the main window
+            label.set_editable(!file_node.path().is_empty());
+
+            let name_binding = file_node
+                .bind_property("name", &label, "text")
+                .sync_create()
+                .build();
+
             // Use property bindings so the checkbox updates when the FileNode changes
             let wanted_binding = file_node
                 .bind_property("wanted", &check_button, "active")
@@ -431,133 +1031,326 @@ impl SimpleComponent for FileSelectDialogModel {
                 ));
             });
 
+            // Submit a rename once editing finishes with a different name
+            let sender = sender_clone.clone();
+            let node_clone = file_node.clone();
+            let row_clone = tree_list_row.clone();
+            let rename_handler_id = label.connect_editing_notify(move |label| {
+                let new_name = label.text().to_string();
+                if !label.is_editing() && new_name != node_clone.name() {
+                    sender.input(FileSelectDialogInput::Rename(
+                        node_clone.clone(),
+                        row_clone.clone(),
+                        new_name,
+                    ));
+                }
+            });
+
             // Store bindings and handler for later cleanup
             bindings_for_bind.borrow_mut().insert(
                 key,
                 ItemBindings {
-                    handler_id,
-                    wanted_binding,
-                    inconsistent_binding,
+                    bindings: vec![wanted_binding, inconsistent_binding, name_binding],
+                    handlers: vec![
+                        (check_button.upcast(), handler_id),
+                        (label.upcast(), rename_handler_id),
+                    ],
                 },
             );
         });
 
-        let bindings_for_unbind = bindings_storage.clone();
-        factory.connect_unbind(move |_, list_item| {
+        Self::connect_unbind(&factory, bindings_storage);
+
+        factory
+    }
+
+    fn size_factory() -> gtk::SignalListItemFactory {
+        let factory = gtk::SignalListItemFactory::new();
+
+        factory.connect_setup(|_, list_item| {
             let list_item = list_item
                 .downcast_ref::<gtk::ListItem>()
                 .expect("Needs to be ListItem");
 
-            // Use same pointer address key
+            let label = gtk::Label::new(None);
+            label.set_xalign(1.0);
+            label.add_css_class("dim-label");
+            list_item.set_child(Some(&label));
+        });
+
+        factory.connect_bind(|_, list_item| {
+            let (list_item, _, file_node) = bound_item(list_item);
+
+            let label = list_item
+                .child()
+                .and_downcast::<gtk::Label>()
+                .expect("Needs to be Label");
+
+            label.set_text(&glib::format_size(file_node.size() as u64));
+        });
+
+        factory
+    }
+
+    fn progress_factory() -> gtk::SignalListItemFactory {
+        let factory = gtk::SignalListItemFactory::new();
+
+        factory.connect_setup(|_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<gtk::ListItem>()
+                .expect("Needs to be ListItem");
+
+            let progress_bar = gtk::ProgressBar::new();
+            progress_bar.set_valign(gtk::Align::Center);
+            list_item.set_child(Some(&progress_bar));
+        });
+
+        factory.connect_bind(|_, list_item| {
+            let (list_item, _, file_node) = bound_item(list_item);
+
+            let progress_bar = list_item
+                .child()
+                .and_downcast::<gtk::ProgressBar>()
+                .expect("Needs to be ProgressBar");
+
+            progress_bar.set_fraction(file_node.progress());
+            progress_bar.set_tooltip_text(Some(&format!(
+                "{} / {}",
+                glib::format_size(file_node.bytes_completed() as u64),
+                glib::format_size(file_node.size() as u64)
+            )));
+        });
+
+        factory
+    }
+
+    fn priority_factory(
+        sender: &ComponentSender<Self>,
+        bindings_storage: &BindingsStorage,
+    ) -> gtk::SignalListItemFactory {
+        let factory = gtk::SignalListItemFactory::new();
+
+        factory.connect_setup(|_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<gtk::ListItem>()
+                .expect("Needs to be ListItem");
+
+            let priority_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            for priority in FilePriority::ALL {
+                let button = gtk::Button::with_label(&priority.label());
+                button.add_css_class("flat");
+                priority_box.append(&button);
+            }
+
+            let priority_popover = gtk::Popover::new();
+            priority_popover.set_child(Some(&priority_box));
+
+            let priority_button = gtk::MenuButton::new();
+            priority_button.set_tooltip_text(Some(&fl!("file-priority-tooltip")));
+            priority_button.set_popover(Some(&priority_popover));
+            priority_button.add_css_class("flat");
+            list_item.set_child(Some(&priority_button));
+        });
+
+        let sender_clone = sender.clone();
+        let bindings_for_bind = bindings_storage.clone();
+        factory.connect_bind(move |_, list_item| {
+            let (list_item, tree_list_row, file_node) = bound_item(list_item);
             let key = list_item.as_ptr() as usize;
 
-            // Remove and cleanup bindings for this ListItem
-            if let Some(bindings) = bindings_for_unbind.borrow_mut().remove(&key) {
-                if let Some(expander) = list_item.child().and_downcast::<gtk::TreeExpander>() {
-                    if let Some(content_box) = expander.child().and_downcast::<gtk::Box>() {
-                        if let Some(check_button) =
-                            content_box.first_child().and_downcast::<gtk::CheckButton>()
-                        {
-                            check_button.disconnect(bindings.handler_id);
-                        }
-                    }
-                }
-                bindings.wanted_binding.unbind();
-                bindings.inconsistent_binding.unbind();
+            let priority_button = list_item
+                .child()
+                .and_downcast::<gtk::MenuButton>()
+                .expect("Needs to be MenuButton");
+
+            let priority_binding = file_node
+                .bind_property("priority-label", &priority_button, "label")
+                .sync_create()
+                .build();
+
+            // Each button in the popover sets one priority, in the order of FilePriority::ALL
+            let mut handlers = Vec::new();
+            let mut priority_child = priority_button
+                .popover()
+                .and_then(|popover| popover.child())
+                .and_then(|priority_box| priority_box.first_child());
+            for priority in FilePriority::ALL {
+                let Some(button) = priority_child.and_downcast::<gtk::Button>() else {
+                    break;
+                };
+
+                let sender = sender_clone.clone();
+                let node_clone = file_node.clone();
+                let row_clone = tree_list_row.clone();
+                let menu_button = priority_button.clone();
+                let handler_id = button.connect_clicked(move |_| {
+                    menu_button.popdown();
+                    sender.input(FileSelectDialogInput::SetPriority(
+                        node_clone.clone(),
+                        row_clone.clone(),
+                        priority,
+                    ));
+                });
+
+                priority_child = button.next_sibling();
+                handlers.push((button.upcast(), handler_id));
             }
+
+            bindings_for_bind.borrow_mut().insert(
+                key,
+                ItemBindings {
+                    bindings: vec![priority_binding],
+                    handlers,
+                },
+            );
         });
 
-        // Store factory in list view
-        widgets.list_view.set_factory(Some(&factory));
+        Self::connect_unbind(&factory, bindings_storage);
 
-        // Store the list view reference in the model
-        let mut model = model;
-        model.list_view = Some(widgets.list_view.clone());
+        factory
+    }
 
-        ComponentParts { model, widgets }
+    fn actions_factory(
+        sender: &ComponentSender<Self>,
+        bindings_storage: &BindingsStorage,
+    ) -> gtk::SignalListItemFactory {
+        let factory = gtk::SignalListItemFactory::new();
+
+        factory.connect_setup(|_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<gtk::ListItem>()
+                .expect("Needs to be ListItem");
+
+            let actions_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+
+            let open_button = gtk::Button::from_icon_name("document-open-symbolic");
+            open_button.set_tooltip_text(Some(&fl!("action-open-file")));
+            open_button.add_css_class("flat");
+            actions_box.append(&open_button);
+
+            let folder_button = gtk::Button::from_icon_name("folder-open-symbolic");
+            folder_button.set_tooltip_text(Some(&fl!("action-show-in-filemanager")));
+            folder_button.add_css_class("flat");
+            actions_box.append(&folder_button);
+
+            list_item.set_child(Some(&actions_box));
+        });
+
+        let sender_clone = sender.clone();
+        let bindings_for_bind = bindings_storage.clone();
+        factory.connect_bind(move |_, list_item| {
+            let (list_item, _, file_node) = bound_item(list_item);
+            let key = list_item.as_ptr() as usize;
+
+            let actions_box = list_item
+                .child()
+                .and_downcast::<gtk::Box>()
+                .expect("Needs to be Box");
+
+            let open_button = actions_box
+                .first_child()
+                .and_downcast::<gtk::Button>()
+                .expect("Needs to be Button");
+
+            let folder_button = open_button
+                .next_sibling()
+                .and_downcast::<gtk::Button>()
+                .expect("Needs to be Button");
+
+            // Only finished files exist on disk under their final name
+            actions_box.set_visible(!file_node.is_folder() && file_node.is_complete());
+
+            let sender = sender_clone.clone();
+            let node_clone = file_node.clone();
+            let open_handler = open_button.connect_clicked(move |_| {
+                sender.input(FileSelectDialogInput::OpenFile(node_clone.clone()));
+            });
+
+            let sender = sender_clone.clone();
+            let node_clone = file_node.clone();
+            let folder_handler = folder_button.connect_clicked(move |_| {
+                sender.input(FileSelectDialogInput::ShowFileInFolder(node_clone.clone()));
+            });
+
+            bindings_for_bind.borrow_mut().insert(
+                key,
+                ItemBindings {
+                    bindings: vec![],
+                    handlers: vec![
+                        (open_button.upcast(), open_handler),
+                        (folder_button.upcast(), folder_handler),
+                    ],
+                },
+            );
+        });
+
+        Self::connect_unbind(&factory, bindings_storage);
+
+        factory
     }
 
-    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
-        match message {
-            FileSelectDialogInput::Open(torrent_hash, torrent_id, torrent_name, files) => {
-                self.torrent_hash = torrent_hash;
-                self.torrent_id = torrent_id;
-                self.torrent_name = torrent_name.clone();
-                self.files = files;
-                self.populate_tree();
-                // Update the list view with the new model
-                if let (Some(ref tree_model), Some(ref list_view)) =
-                    (&self.tree_model, &self.list_view)
-                {
-                    let selection_model = gtk::NoSelection::new(Some(tree_model.clone()));
-                    list_view.set_model(Some(&selection_model));
+    fn connect_unbind(factory: &gtk::SignalListItemFactory, bindings_storage: &BindingsStorage) {
+        let bindings_for_unbind = bindings_storage.clone();
+        factory.connect_unbind(move |_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<gtk::ListItem>()
+                .expect("Needs to be ListItem");
+
+            // Use same pointer address key
+            let key = list_item.as_ptr() as usize;
+
+            // Remove and cleanup bindings for this ListItem
+            if let Some(bindings) = bindings_for_unbind.borrow_mut().remove(&key) {
+                for (object, handler_id) in bindings.handlers {
+                    object.disconnect(handler_id);
+                }
+                for binding in bindings.bindings {
+                    binding.unbind();
                 }
-                self.visible = true;
-            }
-            FileSelectDialogInput::Close => {
-                self.visible = false;
-                // Collect wanted and unwanted files
-                let (wanted, unwanted) = self.collect_file_states();
-                sender
-                    .output(FileSelectDialogOutput::UpdateFiles(
-                        self.torrent_hash.clone(),
-                        self.torrent_id,
-                        wanted,
-                        unwanted,
-                    ))
-                    .ok();
-            }
-            FileSelectDialogInput::ToggleFile(file_node, tree_row, new_state) => {
-                self.toggle_file(&file_node, &tree_row, new_state);
             }
-        }
+        });
     }
-}
 
-impl FileSelectDialogModel {
     fn populate_tree(&mut self) {
         self.root_store.remove_all();
 
-        let root_node = FileNode::new_folder(&self.torrent_name, "");
+        let entries = Self::file_entries(&self.files);
+        self.original_states = entries.iter().map(|entry| entry.state).collect();
 
-        // Build file tree structure
-        for (index, file) in self.files.files.iter().enumerate() {
-            let path_parts: Vec<&str> = file.name.split('/').collect();
-            let wanted = self
-                .files
-                .wanted
-                .get(index)
-                .map(|w| *w != 0)
-                .unwrap_or(true);
-
-            Self::insert_into_tree(&root_node, &path_parts, index, wanted);
-        }
+        let root_node = Self::build_tree(&self.torrent_name, &entries);
+        self.root_store.append(&root_node);
 
-        root_node.update_state_from_children();
+        self.refresh_tree_model();
+    }
 
-        self.root_store.append(&root_node);
+    /// Rebuild the tree model, hiding nodes that don't match the search term
+    fn refresh_tree_model(&mut self) {
+        let search_term_lower = self.search_term.to_lowercase();
+        let searching = !search_term_lower.is_empty();
+        let search_results = match self.root_node() {
+            Some(root_node) if searching => Some(root_node.search_results(&search_term_lower)),
+            _ => None,
+        };
 
         let tree_model = gtk::TreeListModel::new(
             self.root_store.clone(),
-            false, // not passthrough - we want TreeListRow items
-            false, // not autoexpand
-            |item| -> Option<gio::ListModel> {
+            false,     // not passthrough - we want TreeListRow items
+            searching, // expand everything so that matches deep in the tree are visible
+            move |item| -> Option<gio::ListModel> {
                 let file_node = item.downcast_ref::<FileNode>()?;
                 let children = file_node.children();
                 if children.is_empty() {
                     return None;
                 }
 
-                // Sort children: folders first, then files, both alphabetically
-                let mut sorted_children = children;
-                sorted_children.sort_by(|a, b| match (a.is_folder(), b.is_folder()) {
-                    (true, false) => std::cmp::Ordering::Less,
-                    (false, true) => std::cmp::Ordering::Greater,
-                    _ => a.name().to_lowercase().cmp(&b.name().to_lowercase()),
-                });
-
+                // Ordering is handled by the column view's sorter
                 let store = gio::ListStore::new::<FileNode>();
-                for child in sorted_children {
+                for child in children {
+                    if let Some(results) = &search_results {
+                        if !results.contains(&child) {
+                            continue;
+                        }
+                    }
                     store.append(&child);
                 }
                 Some(store.upcast())
@@ -569,10 +1362,152 @@ impl FileSelectDialogModel {
             row.set_expanded(true);
         }
 
+        // Update the column view with the new model
+        if let Some(ref column_view) = self.column_view {
+            let sort_model =
+                gtk::SortListModel::new(Some(tree_model.clone()), self.row_sorter.clone());
+            let selection_model = gtk::NoSelection::new(Some(sort_model));
+            column_view.set_model(Some(&selection_model));
+        }
+
         self.tree_model = Some(tree_model);
     }
 
-    fn insert_into_tree(parent: &FileNode, path_parts: &[&str], index: usize, wanted: bool) {
+    fn root_node(&self) -> Option<FileNode> {
+        self.root_store.item(0).and_downcast::<FileNode>()
+    }
+
+    /// Set the wanted state of every file matching the current search
+    fn set_matching_files_wanted<F>(&mut self, wanted: F)
+    where
+        F: Fn(&FileNode) -> bool,
+    {
+        let Some(root_node) = self.root_node() else {
+            return;
+        };
+
+        let search_term_lower = self.search_term.to_lowercase();
+        let search_results = root_node.search_results(&search_term_lower);
+        for file_node in root_node.files() {
+            if search_term_lower.is_empty() || search_results.contains(&file_node) {
+                file_node.set_wanted(wanted(&file_node));
+            }
+        }
+
+        root_node.update_state_from_children();
+        self.update_summary();
+    }
+
+    fn populate_type_menu(&self, sender: &ComponentSender<Self>) {
+        while let Some(child) = self.type_box.first_child() {
+            self.type_box.remove(&child);
+        }
+
+        let files = self
+            .root_node()
+            .map(|node| node.files())
+            .unwrap_or_default();
+
+        let mut media_types: Vec<String> = files.iter().filter_map(|f| f.media_type()).collect();
+        media_types.sort();
+        media_types.dedup();
+
+        let mut extensions: Vec<String> = files.iter().filter_map(|f| f.extension()).collect();
+        extensions.sort();
+        extensions.dedup();
+
+        let type_filters = media_types
+            .into_iter()
+            .map(FileTypeFilter::MediaType)
+            .chain(extensions.into_iter().map(FileTypeFilter::Extension));
+
+        for type_filter in type_filters {
+            let button = gtk::Button::with_label(&type_filter.label());
+            button.add_css_class("flat");
+
+            let sender = sender.clone();
+            let type_box = self.type_box.clone();
+            button.connect_clicked(move |_| {
+                if let Some(popover) = type_box
+                    .ancestor(gtk::Popover::static_type())
+                    .and_downcast::<gtk::Popover>()
+                {
+                    popover.popdown();
+                }
+                sender.input(FileSelectDialogInput::SelectByType(type_filter.clone()));
+            });
+
+            self.type_box.append(&button);
+        }
+    }
+
+    fn update_summary(&mut self) {
+        let files = self
+            .root_node()
+            .map(|node| node.files())
+            .unwrap_or_default();
+        let wanted: Vec<&FileNode> = files.iter().filter(|f| f.wanted()).collect();
+
+        let selected_size: i64 = wanted.iter().map(|f| f.size()).sum();
+        let total_size: i64 = files.iter().map(|f| f.size()).sum();
+
+        self.summary = fl!(
+            "file-selection-summary",
+            files = wanted.len(),
+            selected = glib::format_size(selected_size as u64).to_string(),
+            total = glib::format_size(total_size as u64).to_string()
+        );
+    }
+
+    fn file_entries(files: &TorrentFiles) -> Vec<FileEntry> {
+        files
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| FileEntry {
+                name: file.name.clone(),
+                length: file.length,
+                bytes_completed: files
+                    .file_stats
+                    .get(index)
+                    .map(|stats| stats.bytes_completed)
+                    .unwrap_or(file.bytes_completed),
+                state: FileState {
+                    wanted: files.wanted.get(index).map(|w| *w != 0).unwrap_or(true),
+                    priority: files
+                        .priorities
+                        .get(index)
+                        .map(|p| FilePriority::from(*p))
+                        .unwrap_or(FilePriority::Normal),
+                },
+            })
+            .collect()
+    }
+
+    /// Build the folder hierarchy for a torrent's files, with folder states aggregated
+    fn build_tree(torrent_name: &str, entries: &[FileEntry]) -> FileNode {
+        let root_node = FileNode::new_folder(torrent_name, "");
+
+        for (index, entry) in entries.iter().enumerate() {
+            let path_parts: Vec<&str> = entry.name.split('/').collect();
+
+            let file_node = FileNode::new_file(
+                path_parts.last().copied().unwrap_or_default(),
+                index,
+                entry.state.wanted,
+                entry.state.priority,
+            );
+            file_node.set_sizes(entry.length, entry.bytes_completed);
+
+            Self::insert_into_tree(&root_node, &path_parts, file_node);
+        }
+
+        root_node.update_state_from_children();
+
+        root_node
+    }
+
+    fn insert_into_tree(parent: &FileNode, path_parts: &[&str], file_node: FileNode) {
         if path_parts.is_empty() {
             return;
         }
@@ -580,18 +1515,20 @@ impl FileSelectDialogModel {
         let current_name = path_parts[0];
         let is_leaf = path_parts.len() == 1;
 
+        // Relative to the download folder, for opening the file or showing it in its folder
+        let current_path = if parent.path().is_empty() {
+            current_name.to_string()
+        } else {
+            format!("{}/{}", parent.path(), current_name)
+        };
+
         if is_leaf {
-            let file_node = FileNode::new_file(current_name, &path_parts.join("/"), index, wanted);
+            file_node.imp().path.replace(current_path);
             parent.add_child(file_node);
         } else {
             // Find or create folder
-            let current_path = if parent.path().is_empty() {
-                current_name.to_string()
-            } else {
-                format!("{}/{}", parent.path(), current_name)
-            };
             let folder = parent.find_or_create_folder(current_name, &current_path);
-            Self::insert_into_tree(&folder, &path_parts[1..], index, wanted);
+            Self::insert_into_tree(&folder, &path_parts[1..], file_node);
         }
     }
 
@@ -605,6 +1542,21 @@ impl FileSelectDialogModel {
         self.update_parent_states(tree_row);
     }
 
+    fn set_file_priority(
+        &mut self,
+        file_node: &FileNode,
+        tree_row: &gtk::TreeListRow,
+        priority: FilePriority,
+    ) {
+        if file_node.is_folder() {
+            file_node.set_priority_recursive(priority);
+        } else {
+            file_node.set_priority(priority);
+        }
+
+        self.update_parent_states(tree_row);
+    }
+
     fn update_parent_states(&self, tree_row: &gtk::TreeListRow) {
         // Walk up the tree and update each parent's state
         let mut current_row = tree_row.parent();
@@ -616,36 +1568,223 @@ impl FileSelectDialogModel {
         }
     }
 
-    fn collect_file_states(&self) -> (Vec<i32>, Vec<i32>) {
-        let mut wanted = Vec::new();
-        let mut unwanted = Vec::new();
+    /// Re-apply changes made before the tree was rebuilt, where the user had changed a file
+    fn restore_pending_states(&self, original_states: &[FileState], current_states: &[FileState]) {
+        let Some(root_node) = self.root_node() else {
+            return;
+        };
 
-        for i in 0..self.root_store.n_items() {
-            if let Some(root_node) = self.root_store.item(i).and_downcast::<FileNode>() {
-                Self::collect_file_states_recursive(&root_node, &mut wanted, &mut unwanted);
+        for file_node in root_node.files() {
+            let index = file_node.index() as usize;
+            if let (Some(original), Some(current)) =
+                (original_states.get(index), current_states.get(index))
+            {
+                if original != current {
+                    file_node.set_wanted(current.wanted);
+                    file_node.set_priority(current.priority);
+                }
             }
         }
 
-        (wanted, unwanted)
+        root_node.update_state_from_children();
     }
 
-    fn collect_file_states_recursive(
-        node: &FileNode,
-        wanted: &mut Vec<i32>,
-        unwanted: &mut Vec<i32>,
-    ) {
-        if node.is_folder() {
-            for child in node.children() {
-                Self::collect_file_states_recursive(&child, wanted, unwanted);
-            }
-        } else {
-            // Set the wanted/unwanted lists based on this file's state
-            let index = node.index();
-            if node.wanted() {
-                wanted.push(index);
-            } else {
-                unwanted.push(index);
+    /// The state of every file in the tree, indexed by file index
+    fn current_states(&self) -> Vec<FileState> {
+        let mut states = self.original_states.clone();
+
+        if let Some(root_node) = self.root_node() {
+            for file_node in root_node.files() {
+                if let Some(state) = states.get_mut(file_node.index() as usize) {
+                    *state = FileState {
+                        wanted: file_node.wanted(),
+                        priority: file_node.priority(),
+                    };
+                }
             }
         }
+
+        states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, length: i64, bytes_completed: i64, wanted: bool) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            length,
+            bytes_completed,
+            state: FileState {
+                wanted,
+                priority: FilePriority::Normal,
+            },
+        }
+    }
+
+    fn find_child(node: &FileNode, name: &str) -> FileNode {
+        node.children()
+            .into_iter()
+            .find(|child| child.name() == name)
+            .expect("child should exist")
+    }
+
+    #[test]
+    fn build_tree_nests_files_into_folders() {
+        let entries = vec![
+            entry("Album/CD1/01.flac", 100, 100, true),
+            entry("Album/CD1/02.flac", 100, 50, true),
+            entry("Album/cover.jpg", 10, 0, false),
+        ];
+
+        let root = FileSelectDialogModel::build_tree("Album", &entries);
+        let album = find_child(&root, "Album");
+        let cd1 = find_child(&album, "CD1");
+
+        assert!(cd1.is_folder());
+        assert_eq!(cd1.path(), "Album/CD1");
+        assert_eq!(cd1.children().len(), 2);
+
+        let cover = find_child(&album, "cover.jpg");
+        assert!(!cover.is_folder());
+        assert_eq!(cover.index(), 2);
+        assert_eq!(cover.path(), "Album/cover.jpg");
+    }
+
+    #[test]
+    fn build_tree_aggregates_folder_state() {
+        let entries = vec![
+            entry("Album/CD1/01.flac", 100, 100, true),
+            entry("Album/CD1/02.flac", 100, 50, true),
+            entry("Album/cover.jpg", 10, 0, false),
+        ];
+
+        let root = FileSelectDialogModel::build_tree("Album", &entries);
+        let album = find_child(&root, "Album");
+        let cd1 = find_child(&album, "CD1");
+
+        assert_eq!(cd1.size(), 200);
+        assert_eq!(cd1.bytes_completed(), 150);
+        assert!(cd1.wanted());
+        assert!(!cd1.inconsistent());
+
+        assert_eq!(album.size(), 210);
+        assert!(album.wanted());
+        assert!(album.inconsistent());
+    }
+
+    #[test]
+    fn build_tree_marks_mixed_priorities() {
+        let mut entries = vec![
+            entry("Show/e01.mkv", 100, 0, true),
+            entry("Show/e02.mkv", 100, 0, true),
+        ];
+        entries[1].state.priority = FilePriority::High;
+
+        let root = FileSelectDialogModel::build_tree("Show", &entries);
+        let show = find_child(&root, "Show");
+        assert!(show.priority_mixed());
+
+        show.set_priority_recursive(FilePriority::Low);
+        root.update_state_from_children();
+        assert!(!show.priority_mixed());
+        assert_eq!(root.priority(), FilePriority::Low);
+    }
+
+    #[test]
+    fn search_shows_matches_and_the_folders_leading_to_them() {
+        let entries = vec![
+            entry("Show/Season 1/e01.mkv", 100, 0, true),
+            entry("Show/Season 2/e01.mkv", 100, 0, true),
+            entry("Show/Season 2/e02.mkv", 100, 0, true),
+            entry("Show/Extras/Trailer.mkv", 10, 0, true),
+        ];
+        let root = FileSelectDialogModel::build_tree("Show", &entries);
+        let show = find_child(&root, "Show");
+        let season_2 = find_child(&show, "Season 2");
+
+        let results = root.search_results("e02");
+        assert_eq!(
+            results,
+            HashSet::from([
+                show.clone(),
+                season_2.clone(),
+                find_child(&season_2, "e02.mkv")
+            ])
+        );
+        assert!(root.search_results("nothing").is_empty());
+    }
+
+    #[test]
+    fn search_shows_everything_inside_a_matching_folder() {
+        let entries = vec![
+            entry("Show/Season 1/e01.mkv", 100, 0, true),
+            entry("Show/Season 1/Subs/e01.srt", 1, 0, true),
+            entry("Show/Season 2/e01.mkv", 100, 0, true),
+        ];
+        let root = FileSelectDialogModel::build_tree("Show", &entries);
+        let show = find_child(&root, "Show");
+        let season_1 = find_child(&show, "Season 1");
+        let subs = find_child(&season_1, "Subs");
+
+        let results = root.search_results("season 1");
+        assert!(results.contains(&show));
+        assert!(results.contains(&season_1));
+        assert!(results.contains(&subs));
+        for file in season_1.files() {
+            assert!(results.contains(&file), "{} should be shown", file.path());
+        }
+        assert!(!results.contains(&find_child(&show, "Season 2")));
+    }
+
+    #[test]
+    fn diff_of_unchanged_states_is_empty() {
+        let states = vec![
+            FileState {
+                wanted: true,
+                priority: FilePriority::Normal,
+            },
+            FileState {
+                wanted: false,
+                priority: FilePriority::High,
+            },
+        ];
+
+        assert!(diff_file_states(&states, &states).is_empty());
+    }
+
+    #[test]
+    fn diff_only_contains_changed_files() {
+        let original = vec![
+            FileState {
+                wanted: true,
+                priority: FilePriority::Normal,
+            },
+            FileState {
+                wanted: true,
+                priority: FilePriority::Normal,
+            },
+            FileState {
+                wanted: false,
+                priority: FilePriority::Low,
+            },
+        ];
+        let mut current = original.clone();
+        current[1].wanted = false;
+        current[2].wanted = true;
+        current[2].priority = FilePriority::High;
+
+        assert_eq!(
+            diff_file_states(&original, &current),
+            FileSelection {
+                wanted: vec![2],
+                unwanted: vec![1],
+                priority_high: vec![2],
+                priority_normal: vec![],
+                priority_low: vec![],
+            }
+        );
     }
 }
@@ -0,0 +1,117 @@
+use std::net::IpAddr;
+use std::path::Path;
+
+/// An offline IP to country database, loaded from a CSV file of `start,end,country` ranges
+/// such as the free "IP to Country Lite" download from db-ip.com
+#[derive(Debug, Default)]
+pub struct GeoIpDatabase {
+    ranges: Vec<(u128, u128, String)>,
+}
+
+fn ip_to_u128(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped().into(),
+        IpAddr::V6(ip) => ip.into(),
+    }
+}
+
+impl GeoIpDatabase {
+    /// Read and parse the database file, which is large enough that this shouldn't happen on
+    /// the UI thread
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Parse the CSV contents, skipping any lines that aren't an address range
+    pub fn parse(contents: &str) -> Self {
+        let mut ranges: Vec<(u128, u128, String)> = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(',').map(|field| field.trim().trim_matches('"'));
+                let start = fields.next()?.parse::<IpAddr>().ok()?;
+                let end = fields.next()?.parse::<IpAddr>().ok()?;
+                let country = fields.next()?;
+                Some((ip_to_u128(start), ip_to_u128(end), country.to_string()))
+            })
+            .collect();
+        ranges.sort_by_key(|(start, _, _)| *start);
+
+        Self { ranges }
+    }
+
+    /// The two letter country code for an address, if it falls within a known range
+    pub fn country(&self, address: &str) -> Option<&str> {
+        let ip = ip_to_u128(address.parse().ok()?);
+
+        // The last range starting at or before the address is the only one that can contain it
+        let index = self.ranges.partition_point(|(start, _, _)| *start <= ip);
+        let (_, end, country) = self.ranges.get(index.checked_sub(1)?)?;
+
+        if ip <= *end {
+            Some(country)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+\"1.0.0.0\",\"1.0.0.255\",\"AU\"
+start,end,country
+2.0.0.0,2.0.0.255,FR
+1.0.1.0,1.0.3.255,CN
+2001:200::,2001:200:ffff:ffff:ffff:ffff:ffff:ffff,JP
+not an address,either,XX
+";
+
+    #[test]
+    fn parse_skips_lines_that_arent_ranges() {
+        let geoip = GeoIpDatabase::parse(CSV);
+        assert_eq!(geoip.ranges.len(), 4);
+    }
+
+    #[test]
+    fn parse_sorts_ranges_by_start() {
+        let geoip = GeoIpDatabase::parse(CSV);
+        let countries: Vec<&str> = geoip
+            .ranges
+            .iter()
+            .map(|(_, _, country)| country.as_str())
+            .collect();
+        assert_eq!(countries, vec!["AU", "CN", "FR", "JP"]);
+    }
+
+    #[test]
+    fn finds_the_country_of_an_address() {
+        let geoip = GeoIpDatabase::parse(CSV);
+        assert_eq!(geoip.country("1.0.0.1"), Some("AU"));
+        assert_eq!(geoip.country("1.0.2.5"), Some("CN"));
+        assert_eq!(geoip.country("2.0.0.255"), Some("FR"));
+        assert_eq!(geoip.country("2001:200::1"), Some("JP"));
+    }
+
+    #[test]
+    fn finds_nothing_outside_the_ranges() {
+        let geoip = GeoIpDatabase::parse(CSV);
+        assert_eq!(geoip.country("0.255.255.255"), None);
+        assert_eq!(geoip.country("1.0.4.0"), None);
+        assert_eq!(geoip.country("3.0.0.0"), None);
+        assert_eq!(geoip.country("2001:201::"), None);
+    }
+
+    #[test]
+    fn finds_nothing_for_invalid_addresses() {
+        let geoip = GeoIpDatabase::parse(CSV);
+        assert_eq!(geoip.country(""), None);
+        assert_eq!(geoip.country("not an address"), None);
+    }
+
+    #[test]
+    fn finds_nothing_in_an_empty_database() {
+        assert_eq!(GeoIpDatabase::default().country("1.0.0.1"), None);
+    }
+}
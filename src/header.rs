@@ -1,23 +1,38 @@
 use crate::fl;
-use gtk::prelude::{ButtonExt, EditableExt, WidgetExt};
+use crate::query::Query;
+use crate::sort::SortKey;
+use gtk::gio;
+use gtk::prelude::{ButtonExt, EditableExt, ToVariant, WidgetExt};
+use relm4::actions::ActionName;
 use relm4::gtk;
 use relm4::ComponentParts;
+use relm4::RelmWidgetExt;
 use relm4::SimpleComponent;
 use relm4_macros::menu;
 
-pub struct HeaderModel;
+pub struct HeaderModel {
+    labels_menu: gio::Menu,
+    has_labels: bool,
+    query_error: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum HeaderInput {
+    LabelsChanged(Vec<(String, usize)>), // every label in use, and how many torrents have it
+    SearchChanged(String),
+}
 
 #[derive(Debug)]
 pub enum HeaderOutput {
     OpenTorrent,
     OpenMagnet,
-    SearchChanged(String),
+    SearchChanged(Query),
 }
 
 #[relm4::component(pub)]
 impl SimpleComponent for HeaderModel {
     type Init = ();
-    type Input = ();
+    type Input = HeaderInput;
     type Output = HeaderOutput;
 
     view! {
@@ -25,12 +40,31 @@ impl SimpleComponent for HeaderModel {
         gtk::HeaderBar {
             set_show_title_buttons: true,
             #[wrap(Some)]
-            set_title_widget = &gtk::SearchEntry {
-                set_hexpand: true,
-                set_placeholder_text: Some("Search Torrents"),
+            set_title_widget = &gtk::Overlay {
                 set_valign: gtk::Align::Center,
-                connect_search_changed[sender] => move |search_entry| {
-                    sender.output(HeaderOutput::SearchChanged(search_entry.text().to_string())).unwrap();
+
+                gtk::SearchEntry {
+                    set_hexpand: true,
+                    set_placeholder_text: Some("Search Torrents"),
+                    set_tooltip_text: Some(&fl!("search-tooltip")),
+                    #[watch]
+                    set_class_active: ("error", model.query_error.is_some()),
+                    connect_search_changed[sender] => move |search_entry| {
+                        sender.input(HeaderInput::SearchChanged(search_entry.text().to_string()));
+                    },
+                },
+
+                // Sits just inside the entry, clear of its own clear button
+                add_overlay = &gtk::Image {
+                    set_icon_name: Some("dialog-error-symbolic"),
+                    set_halign: gtk::Align::End,
+                    set_valign: gtk::Align::Center,
+                    set_margin_end: 30,
+                    add_css_class: "error",
+                    #[watch]
+                    set_visible: model.query_error.is_some(),
+                    #[watch]
+                    set_tooltip_text: model.query_error.as_deref(),
                 },
             },
 
@@ -54,9 +88,17 @@ impl SimpleComponent for HeaderModel {
             },
 
             pack_end = &gtk::MenuButton {
+                set_icon_name: "view-sort-descending-symbolic",
+                set_tooltip_text: Some(&fl!("sort-tooltip")),
+                set_menu_model: Some(&sort_menu),
+            },
+
+            pack_end = &gtk::MenuButton {
+                #[watch]
+                set_visible: model.has_labels,
                 set_icon_name: "filter",
                 set_tooltip_text: Some(&fl!("filter-tooltip")),
-                set_menu_model: Some(&filter_menu),
+                set_menu_model: Some(&labels_menu),
             }
         }
     }
@@ -70,18 +112,79 @@ impl SimpleComponent for HeaderModel {
             main_menu: {
                 &fl!("action-prefs") => crate::PreferencesAction,
                 &fl!("action-quit") => crate::QuitAction,
-            },
-            filter_menu: {
-                &fl!("filter-all") => crate::FilterAction(0),
-                &fl!("filter-downloading") => crate::FilterAction(1),
-                &fl!("filter-seeding") => crate::FilterAction(2),
-                &fl!("filter-paused") => crate::FilterAction(3),
             }
         }
 
-        let model = Self;
+        let sort_keys = gio::Menu::new();
+        for key in SortKey::ALL {
+            let item = gio::MenuItem::new(Some(&key.label()), None);
+            item.set_action_and_target_value(
+                Some(&crate::SortByAction::action_name()),
+                Some(&key.id().to_variant()),
+            );
+            sort_keys.append_item(&item);
+        }
+
+        let sort_direction = gio::Menu::new();
+        sort_direction.append(
+            Some(&fl!("sort-ascending")),
+            Some(&crate::SortAscendingAction::action_name()),
+        );
+
+        let sort_menu = gio::Menu::new();
+        sort_menu.append_section(None, &sort_keys);
+        sort_menu.append_section(None, &sort_direction);
+
+        // The states, trackers and folders are in the sidebar, so this only narrows by label.
+        // Filled in once the torrents are loaded, and only shown if any have labels.
+        let labels_menu = gio::Menu::new();
+
+        let model = HeaderModel {
+            labels_menu: labels_menu.clone(),
+            has_labels: false,
+            query_error: None,
+        };
         let widgets = view_output!();
 
         ComponentParts { model, widgets }
     }
+
+    fn update(&mut self, message: Self::Input, sender: relm4::ComponentSender<Self>) {
+        match message {
+            HeaderInput::SearchChanged(text) => match Query::parse(&text) {
+                Ok(query) => {
+                    self.query_error = None;
+                    sender.output(HeaderOutput::SearchChanged(query)).unwrap();
+                }
+                // Keep showing the results of the last query that made sense
+                Err(err) => self.query_error = Some(err.message()),
+            },
+            HeaderInput::LabelsChanged(label_counts) => {
+                self.labels_menu.remove_all();
+                self.has_labels = !label_counts.is_empty();
+                if label_counts.is_empty() {
+                    return;
+                }
+
+                let all_labels = gio::MenuItem::new(Some(&fl!("filter-all-labels")), None);
+                all_labels.set_action_and_target_value(
+                    Some(&crate::LabelFilterAction::action_name()),
+                    Some(&"".to_variant()),
+                );
+                self.labels_menu.append_item(&all_labels);
+
+                for (label, count) in label_counts {
+                    let item = gio::MenuItem::new(
+                        Some(&fl!("filter-label", label = label.clone(), count = count)),
+                        None,
+                    );
+                    item.set_action_and_target_value(
+                        Some(&crate::LabelFilterAction::action_name()),
+                        Some(&label.to_variant()),
+                    );
+                    self.labels_menu.append_item(&item);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,106 @@
+use gtk::gio;
+use gtk::prelude::{SettingsExtManual, ToVariant};
+use relm4::gtk;
+use std::collections::{BTreeMap, HashMap};
+
+/// Split comma separated text into labels. Transmission doesn't allow commas in labels, so a
+/// comma always separates two of them.
+pub fn parse_labels(text: &str) -> Vec<String> {
+    let mut labels: Vec<String> = vec![];
+    for label in text.split(',').map(str::trim) {
+        if !label.is_empty() && !labels.iter().any(|existing| existing == label) {
+            labels.push(label.to_string());
+        }
+    }
+
+    labels
+}
+
+/// Every label in use and how many torrents have it, in alphabetical order
+pub fn label_counts(labels: impl Iterator<Item = Vec<String>>) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for label in labels.flatten() {
+        *counts.entry(label).or_default() += 1;
+    }
+
+    counts.into_iter().collect()
+}
+
+/// The folders that new torrents with each label are downloaded to, keyed by label
+pub fn label_folders() -> HashMap<String, String> {
+    let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+    settings.get("label-download-folders")
+}
+
+pub fn save_label_folders(folders: &HashMap<String, String>) {
+    let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+    if let Err(err) = settings.set("label-download-folders", folders.to_variant()) {
+        log::error!("Error saving label download folders: {}", err);
+    }
+}
+
+/// Where a new torrent with these labels should be downloaded to, going by the first label
+/// that has a folder set
+pub fn folder_for_labels(labels: &[String]) -> Option<String> {
+    let folders = label_folders();
+    labels
+        .iter()
+        .find_map(|label| folders.get(label))
+        .filter(|folder| !folder.is_empty())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+
+    #[test]
+    fn labels_are_split_on_commas_and_trimmed() {
+        assert_eq!(
+            parse_labels(" films,tv shows ,  linux"),
+            strings(&["films", "tv shows", "linux"])
+        );
+        assert_eq!(parse_labels("films"), strings(&["films"]));
+    }
+
+    #[test]
+    fn empty_labels_are_skipped() {
+        assert!(parse_labels("").is_empty());
+        assert!(parse_labels(" , ,").is_empty());
+        assert_eq!(parse_labels(",films,,"), strings(&["films"]));
+    }
+
+    #[test]
+    fn repeated_labels_are_kept_once_in_their_first_place() {
+        assert_eq!(
+            parse_labels("tv, films, tv ,films, linux"),
+            strings(&["tv", "films", "linux"])
+        );
+        // Labels are case sensitive in Transmission
+        assert_eq!(parse_labels("TV, tv"), strings(&["TV", "tv"]));
+    }
+
+    #[test]
+    fn labels_are_counted_in_alphabetical_order() {
+        let torrents = vec![
+            strings(&["tv", "films"]),
+            strings(&[]),
+            strings(&["films"]),
+            strings(&["linux", "films"]),
+        ];
+
+        assert_eq!(
+            label_counts(torrents.into_iter()),
+            vec![
+                ("films".to_string(), 3),
+                ("linux".to_string(), 1),
+                ("tv".to_string(), 1),
+            ]
+        );
+        assert!(label_counts(std::iter::empty()).is_empty());
+    }
+}
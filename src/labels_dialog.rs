@@ -0,0 +1,334 @@
+use crate::{fl, labels};
+use gtk::prelude::{
+    BoxExt, ButtonExt, CheckButtonExt, DialogExt, EditableExt, EntryExt, FileExt, GtkWindowExt,
+    OrientableExt, WidgetExt,
+};
+use gtk::{gio, pango};
+use relm4::gtk;
+use relm4::ComponentSender;
+use relm4::SimpleComponent;
+
+/// A label in the dialog, and whether the torrents being edited have it. `None` means only
+/// some of them do, and they're left as they are unless the label is toggled.
+struct LabelChoice {
+    name: String,
+    original: Option<bool>,
+    active: Option<bool>,
+    folder: String,
+}
+
+pub struct LabelsDialogModel {
+    dialog: granite::Dialog,
+    hashes: Vec<String>,
+    choices: Vec<LabelChoice>,
+    label_list: gtk::ListBox,
+    new_label_entry: gtk::Entry,
+    visible: bool,
+}
+
+#[derive(Debug)]
+pub enum LabelsDialogInput {
+    Open(Vec<String>, Vec<(String, usize)>), // hashes, every label and how many of the torrents have it
+    Close,
+    AddLabels,
+    Toggle(usize, bool),
+    ChooseFolder(usize),
+    FolderChosen(usize, String),
+    ClearFolder(usize),
+    Submit,
+}
+
+#[derive(Debug)]
+pub enum LabelsDialogOutput {
+    ChangeLabels(Vec<String>, Vec<String>, Vec<String>), // hashes, labels to add, labels to remove
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for LabelsDialogModel {
+    type Init = ();
+    type Input = LabelsDialogInput;
+    type Output = LabelsDialogOutput;
+
+    view! {
+        #[root]
+        granite::Dialog {
+            #[watch]
+            set_visible: model.visible,
+            set_title: Some(&fl!("labels-title")),
+            set_modal: true,
+            set_default_width: 400,
+            set_hide_on_close: true,
+
+            connect_response[sender] => move |_, response_type| {
+                if response_type == gtk::ResponseType::Ok {
+                    sender.input(LabelsDialogInput::Submit);
+                } else {
+                    sender.input(LabelsDialogInput::Close);
+                }
+            },
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_margin_bottom: 12,
+                set_margin_start: 12,
+                set_margin_end: 12,
+                set_margin_top: 12,
+                set_spacing: 12,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    add_css_class: "linked",
+
+                    #[local_ref]
+                    new_label_entry -> gtk::Entry {
+                        set_hexpand: true,
+                        set_placeholder_text: Some(&fl!("labels-new-placeholder")),
+                        connect_activate => LabelsDialogInput::AddLabels,
+                    },
+
+                    gtk::Button {
+                        set_label: &fl!("action-add-label"),
+                        connect_clicked => LabelsDialogInput::AddLabels,
+                    },
+                },
+
+                gtk::ScrolledWindow {
+                    set_propagate_natural_height: true,
+                    set_max_content_height: 250,
+                    add_css_class: granite::STYLE_CLASS_FRAME,
+
+                    #[local_ref]
+                    label_list -> gtk::ListBox {
+                        set_selection_mode: gtk::SelectionMode::None,
+
+                        #[wrap(Some)]
+                        set_placeholder = &gtk::Label {
+                            set_margin_top: 12,
+                            set_margin_bottom: 12,
+                            set_label: &fl!("labels-empty"),
+                            add_css_class: "dim-label",
+                        },
+                    },
+                },
+
+                gtk::Label {
+                    set_halign: gtk::Align::Start,
+                    set_wrap: true,
+                    set_xalign: 0.0,
+                    set_label: &fl!("labels-folder-description"),
+                    add_css_class: granite::STYLE_CLASS_SMALL_LABEL,
+                    add_css_class: "dim-label",
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> relm4::ComponentParts<Self> {
+        let model = LabelsDialogModel {
+            dialog: root.clone(),
+            hashes: vec![],
+            choices: vec![],
+            label_list: gtk::ListBox::new(),
+            new_label_entry: gtk::Entry::new(),
+            visible: false,
+        };
+
+        let label_list = &model.label_list;
+        let new_label_entry = &model.new_label_entry;
+        let widgets = view_output!();
+
+        root.add_button(&fl!("action-cancel"), gtk::ResponseType::Cancel);
+        root.add_button(&fl!("action-apply"), gtk::ResponseType::Ok);
+
+        relm4::ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            LabelsDialogInput::Open(hashes, label_counts) => {
+                let folders = labels::label_folders();
+                self.choices = label_counts
+                    .into_iter()
+                    .map(|(name, count)| {
+                        let state = if count == 0 {
+                            Some(false)
+                        } else if count >= hashes.len() {
+                            Some(true)
+                        } else {
+                            None
+                        };
+
+                        LabelChoice {
+                            folder: folders.get(&name).cloned().unwrap_or_default(),
+                            name,
+                            original: state,
+                            active: state,
+                        }
+                    })
+                    .collect();
+                self.hashes = hashes;
+                self.new_label_entry.set_text("");
+                self.rebuild_rows(&sender);
+                self.visible = !self.hashes.is_empty();
+            }
+            LabelsDialogInput::Close => {
+                self.visible = false;
+            }
+            LabelsDialogInput::AddLabels => {
+                let folders = labels::label_folders();
+                for name in labels::parse_labels(&self.new_label_entry.text()) {
+                    match self.choices.iter_mut().find(|choice| choice.name == name) {
+                        Some(choice) => choice.active = Some(true),
+                        None => self.choices.push(LabelChoice {
+                            folder: folders.get(&name).cloned().unwrap_or_default(),
+                            name,
+                            original: Some(false),
+                            active: Some(true),
+                        }),
+                    }
+                }
+
+                self.new_label_entry.set_text("");
+                self.rebuild_rows(&sender);
+            }
+            LabelsDialogInput::Toggle(index, active) => {
+                if let Some(choice) = self.choices.get_mut(index) {
+                    choice.active = Some(active);
+                }
+            }
+            LabelsDialogInput::ChooseFolder(index) => {
+                let Some(choice) = self.choices.get(index) else {
+                    return;
+                };
+
+                let mut folder_dialog = gtk::FileDialog::builder()
+                    .title(fl!("labels-folder-title", label = choice.name.clone()))
+                    .modal(true);
+                if !choice.folder.is_empty() {
+                    folder_dialog =
+                        folder_dialog.initial_folder(&gio::File::for_path(&choice.folder));
+                }
+
+                let folder_sender = sender.clone();
+                folder_dialog.build().select_folder(
+                    Some(&self.dialog),
+                    gio::Cancellable::NONE,
+                    move |result| {
+                        if let Some(path) = result.ok().and_then(|folder| folder.path()) {
+                            folder_sender.input(LabelsDialogInput::FolderChosen(
+                                index,
+                                path.to_string_lossy().to_string(),
+                            ));
+                        }
+                    },
+                );
+            }
+            LabelsDialogInput::FolderChosen(index, folder) => {
+                if let Some(choice) = self.choices.get_mut(index) {
+                    choice.folder = folder;
+                    self.rebuild_rows(&sender);
+                }
+            }
+            LabelsDialogInput::ClearFolder(index) => {
+                if let Some(choice) = self.choices.get_mut(index) {
+                    choice.folder.clear();
+                    self.rebuild_rows(&sender);
+                }
+            }
+            LabelsDialogInput::Submit => {
+                let mut folders = labels::label_folders();
+                for choice in &self.choices {
+                    if choice.folder.is_empty() {
+                        folders.remove(&choice.name);
+                    } else {
+                        folders.insert(choice.name.clone(), choice.folder.clone());
+                    }
+                }
+                labels::save_label_folders(&folders);
+
+                let changed = |active: bool| {
+                    self.choices
+                        .iter()
+                        .filter(|choice| {
+                            choice.active == Some(active) && choice.original != Some(active)
+                        })
+                        .map(|choice| choice.name.clone())
+                        .collect::<Vec<_>>()
+                };
+                let added = changed(true);
+                let removed = changed(false);
+
+                if !added.is_empty() || !removed.is_empty() {
+                    sender
+                        .output(LabelsDialogOutput::ChangeLabels(
+                            std::mem::take(&mut self.hashes),
+                            added,
+                            removed,
+                        ))
+                        .unwrap();
+                }
+                self.visible = false;
+            }
+        }
+    }
+}
+
+impl LabelsDialogModel {
+    fn rebuild_rows(&self, sender: &ComponentSender<Self>) {
+        while let Some(child) = self.label_list.first_child() {
+            self.label_list.remove(&child);
+        }
+
+        for (index, choice) in self.choices.iter().enumerate() {
+            let check_button = gtk::CheckButton::with_label(&choice.name);
+            check_button.set_hexpand(true);
+            check_button.set_active(choice.active == Some(true));
+            check_button.set_inconsistent(choice.active.is_none());
+
+            let toggle_sender = sender.clone();
+            check_button.connect_toggled(move |button| {
+                button.set_inconsistent(false);
+                toggle_sender.input(LabelsDialogInput::Toggle(index, button.is_active()));
+            });
+
+            let folder_label = gtk::Label::new(Some(&choice.folder));
+            folder_label.set_ellipsize(pango::EllipsizeMode::Start);
+            folder_label.set_max_width_chars(20);
+            folder_label.add_css_class(granite::STYLE_CLASS_SMALL_LABEL);
+            folder_label.add_css_class("dim-label");
+
+            let folder_button = gtk::Button::from_icon_name("folder-symbolic");
+            folder_button.set_tooltip_text(Some(&fl!("labels-folder-tooltip")));
+            folder_button.add_css_class("flat");
+            let folder_sender = sender.clone();
+            folder_button.connect_clicked(move |_| {
+                folder_sender.input(LabelsDialogInput::ChooseFolder(index));
+            });
+
+            let clear_button = gtk::Button::from_icon_name("edit-clear-symbolic");
+            clear_button.set_tooltip_text(Some(&fl!("labels-clear-folder-tooltip")));
+            clear_button.set_visible(!choice.folder.is_empty());
+            clear_button.add_css_class("flat");
+            let clear_sender = sender.clone();
+            clear_button.connect_clicked(move |_| {
+                clear_sender.input(LabelsDialogInput::ClearFolder(index));
+            });
+
+            let row_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            row_box.set_margin_top(3);
+            row_box.set_margin_bottom(3);
+            row_box.set_margin_start(6);
+            row_box.set_margin_end(6);
+            row_box.append(&check_button);
+            row_box.append(&folder_label);
+            row_box.append(&clear_button);
+            row_box.append(&folder_button);
+
+            self.label_list.append(&row_box);
+        }
+    }
+}
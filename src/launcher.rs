@@ -0,0 +1,129 @@
+use crate::rpc::Torrent;
+use crate::torrent::TorrentState;
+use gtk::glib::VariantDict;
+use gtk::prelude::{ApplicationExt, ToVariant};
+use relm4::gtk;
+use std::ops::Deref;
+
+const APP_URI: &str = "application://com.github.davidmhewitt.torrential.desktop";
+const OBJECT_PATH: &str = "/com/github/davidmhewitt/torrential/LauncherEntry";
+
+/// What the dock shows on our launcher through the Unity LauncherEntry API: a progress bar
+/// across everything downloading and a badge counting the downloads
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LauncherEntry {
+    progress: Option<f64>,
+    count: usize,
+}
+
+impl LauncherEntry {
+    pub fn new(torrents: impl IntoIterator<Item = impl Deref<Target = Torrent>>) -> Self {
+        let mut count = 0;
+        let mut total_size = 0.0;
+        let mut done_size = 0.0;
+        for torrent in torrents {
+            if TorrentState::try_from(torrent.status) != Ok(TorrentState::Downloading) {
+                continue;
+            }
+
+            count += 1;
+            total_size += torrent.total_size as f64;
+            done_size += torrent.percent_done as f64 * torrent.total_size as f64;
+        }
+
+        LauncherEntry {
+            // Torrents still fetching metadata don't know their size yet
+            progress: (total_size > 0.0).then(|| done_size / total_size),
+            count,
+        }
+    }
+
+    /// Send the entry to the dock. An idle entry hides the progress bar and badge.
+    pub fn send(&self) {
+        let Some(connection) = relm4::main_application().dbus_connection() else {
+            return;
+        };
+
+        let properties = VariantDict::new(None);
+        properties.insert_value("progress", &self.progress.unwrap_or(0.0).to_variant());
+        properties.insert_value("progress-visible", &self.progress.is_some().to_variant());
+        properties.insert_value("count", &(self.count as i64).to_variant());
+        properties.insert_value("count-visible", &(self.count > 0).to_variant());
+
+        let parameters = (APP_URI, properties.end()).to_variant();
+        if let Err(err) = connection.emit_signal(
+            None,
+            OBJECT_PATH,
+            "com.canonical.Unity.LauncherEntry",
+            "Update",
+            Some(&parameters),
+        ) {
+            log::error!("Error updating launcher entry: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(state: TorrentState, total_size: i64, percent_done: f32) -> Torrent {
+        Torrent {
+            torrent: transmission_client::Torrent {
+                status: state as i32,
+                total_size,
+                percent_done,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn nothing_downloading_is_idle() {
+        assert_eq!(
+            LauncherEntry::new(&[] as &[Torrent]),
+            LauncherEntry::default()
+        );
+        assert_eq!(
+            LauncherEntry::new(&[
+                torrent(TorrentState::Stopped, 1000, 0.5),
+                torrent(TorrentState::Seeding, 1000, 1.0),
+                torrent(TorrentState::DownloadWaiting, 1000, 0.2),
+                torrent(TorrentState::Checking, 1000, 0.7),
+            ]),
+            LauncherEntry::default()
+        );
+    }
+
+    #[test]
+    fn progress_is_weighted_by_size() {
+        let entry = LauncherEntry::new(&[
+            torrent(TorrentState::Downloading, 3000, 1.0 / 3.0),
+            torrent(TorrentState::Downloading, 1000, 1.0),
+            torrent(TorrentState::Seeding, 5000, 1.0),
+        ]);
+        assert_eq!(entry.count, 2);
+        assert!((entry.progress.unwrap() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn torrents_without_metadata_are_counted_without_progress() {
+        let entry = LauncherEntry::new(&[torrent(TorrentState::Downloading, 0, 0.0)]);
+        assert_eq!(
+            entry,
+            LauncherEntry {
+                progress: None,
+                count: 1,
+            }
+        );
+
+        // Once one torrent knows its size, the ones without don't hold the progress back
+        let entry = LauncherEntry::new(&[
+            torrent(TorrentState::Downloading, 0, 0.0),
+            torrent(TorrentState::Downloading, 2000, 0.25),
+        ]);
+        assert_eq!(entry.count, 2);
+        assert!((entry.progress.unwrap() - 0.25).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::fl;
+use gtk::prelude::{
+    BoxExt, ButtonExt, CheckButtonExt, DialogExt, FileExt, GtkWindowExt, OrientableExt, WidgetExt,
+};
+use gtk::{gio, pango};
+use relm4::gtk;
+use relm4::ComponentSender;
+use relm4::SimpleComponent;
+
+pub struct LocationDialogModel {
+    dialog: granite::Dialog,
+    hashes: Vec<String>,
+    location: String,
+    move_data: bool,
+    visible: bool,
+}
+
+#[derive(Debug)]
+pub enum LocationDialogInput {
+    Open(Vec<String>, String, bool), // hashes, current location, move data by default
+    Close,
+    ChooseFolder,
+    FolderChosen(String),
+    SetMoveData(bool),
+    Submit,
+}
+
+#[derive(Debug)]
+pub enum LocationDialogOutput {
+    SetLocation(Vec<String>, String, bool), // hashes, location, move data
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for LocationDialogModel {
+    type Init = ();
+    type Input = LocationDialogInput;
+    type Output = LocationDialogOutput;
+
+    view! {
+        #[root]
+        granite::Dialog {
+            #[watch]
+            set_visible: model.visible,
+            set_title: Some(&fl!("set-location-title")),
+            set_modal: true,
+            set_default_width: 400,
+            set_hide_on_close: true,
+
+            connect_response[sender] => move |_, response_type| {
+                if response_type == gtk::ResponseType::Ok {
+                    sender.input(LocationDialogInput::Submit);
+                } else {
+                    sender.input(LocationDialogInput::Close);
+                }
+            },
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_margin_bottom: 12,
+                set_margin_start: 12,
+                set_margin_end: 12,
+                set_margin_top: 12,
+                set_spacing: 12,
+
+                gtk::Button {
+                    connect_clicked => LocationDialogInput::ChooseFolder,
+
+                    #[wrap(Some)]
+                    set_child = &gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 3,
+
+                        gtk::Image {
+                            set_icon_name: Some("folder"),
+                        },
+
+                        gtk::Label {
+                            set_ellipsize: pango::EllipsizeMode::Start,
+                            #[watch]
+                            set_label: &model.location,
+                        },
+                    },
+                },
+
+                gtk::CheckButton {
+                    set_label: Some(&fl!("set-location-move-data")),
+                    #[watch]
+                    set_active: model.move_data,
+                    connect_toggled[sender] => move |button| {
+                        sender.input(LocationDialogInput::SetMoveData(button.is_active()));
+                    },
+                },
+
+                gtk::Label {
+                    set_halign: gtk::Align::Start,
+                    set_wrap: true,
+                    set_xalign: 0.0,
+                    add_css_class: granite::STYLE_CLASS_SMALL_LABEL,
+                    add_css_class: "dim-label",
+                    #[watch]
+                    set_label: &if model.move_data {
+                        fl!("set-location-move-data-description")
+                    } else {
+                        fl!("set-location-repoint-description")
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> relm4::ComponentParts<Self> {
+        let model = LocationDialogModel {
+            dialog: root.clone(),
+            hashes: vec![],
+            location: String::new(),
+            move_data: true,
+            visible: false,
+        };
+
+        let widgets = view_output!();
+
+        root.add_button(&fl!("action-cancel"), gtk::ResponseType::Cancel);
+        root.add_button(&fl!("action-apply"), gtk::ResponseType::Ok);
+
+        relm4::ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            LocationDialogInput::Open(hashes, location, move_data) => {
+                self.hashes = hashes;
+                self.location = location;
+                self.move_data = move_data;
+                self.visible = true;
+            }
+            LocationDialogInput::Close => {
+                self.visible = false;
+            }
+            LocationDialogInput::ChooseFolder => {
+                let folder_sender = sender.clone();
+                gtk::FileDialog::builder()
+                    .title(fl!("set-location-title"))
+                    .initial_folder(&gio::File::for_path(&self.location))
+                    .modal(true)
+                    .build()
+                    .select_folder(Some(&self.dialog), gio::Cancellable::NONE, move |result| {
+                        if let Some(path) = result.ok().and_then(|folder| folder.path()) {
+                            folder_sender.input(LocationDialogInput::FolderChosen(
+                                path.to_string_lossy().to_string(),
+                            ));
+                        }
+                    });
+            }
+            LocationDialogInput::FolderChosen(location) => {
+                self.location = location;
+            }
+            LocationDialogInput::SetMoveData(move_data) => {
+                self.move_data = move_data;
+            }
+            LocationDialogInput::Submit => {
+                if !self.hashes.is_empty() && !self.location.is_empty() {
+                    sender
+                        .output(LocationDialogOutput::SetLocation(
+                            std::mem::take(&mut self.hashes),
+                            self.location.clone(),
+                            self.move_data,
+                        ))
+                        .unwrap();
+                }
+                self.visible = false;
+            }
+        }
+    }
+}
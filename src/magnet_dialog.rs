@@ -1,3 +1,4 @@
+use crate::{fl, labels};
 use gtk::prelude::{
     BoxExt, DialogExt, EditableExt, EntryExt, GtkWindowExt, OrientableExt, WidgetExt,
 };
@@ -7,6 +8,8 @@ use relm4::SimpleComponent;
 
 pub struct MagnetDialogModel {
     magnet_link: String,
+    labels: String,
+    labels_entry: gtk::Entry,
     visible: bool,
 }
 
@@ -15,12 +18,13 @@ pub enum MagnetDialogInput {
     Open,
     Close,
     UpdateMagnetLink(String),
+    UpdateLabels(String),
     Submit,
 }
 
 #[derive(Debug)]
 pub enum MagnetDialogOutput {
-    AddMagnet(String),
+    AddMagnet(String, Vec<String>), // magnet link, labels
     Close,
 }
 
@@ -70,6 +74,17 @@ impl SimpleComponent for MagnetDialogModel {
                         sender.input(MagnetDialogInput::Submit);
                     },
                 },
+
+                #[local_ref]
+                labels_entry -> gtk::Entry {
+                    set_placeholder_text: Some(&fl!("labels-entry-placeholder")),
+                    connect_text_notify[sender] => move |entry| {
+                        sender.input(MagnetDialogInput::UpdateLabels(entry.text().to_string()));
+                    },
+                    connect_activate[sender] => move |_| {
+                        sender.input(MagnetDialogInput::Submit);
+                    },
+                },
             }
         }
     }
@@ -81,9 +96,12 @@ impl SimpleComponent for MagnetDialogModel {
     ) -> relm4::ComponentParts<Self> {
         let model = MagnetDialogModel {
             magnet_link: String::new(),
+            labels: String::new(),
+            labels_entry: gtk::Entry::new(),
             visible: false,
         };
 
+        let labels_entry = &model.labels_entry;
         let widgets = view_output!();
 
         // Add buttons to the root dialog
@@ -98,6 +116,7 @@ impl SimpleComponent for MagnetDialogModel {
             MagnetDialogInput::Open => {
                 self.visible = true;
                 self.magnet_link.clear();
+                self.labels_entry.set_text("");
             }
             MagnetDialogInput::Close => {
                 self.visible = false;
@@ -106,10 +125,16 @@ impl SimpleComponent for MagnetDialogModel {
             MagnetDialogInput::UpdateMagnetLink(link) => {
                 self.magnet_link = link;
             }
+            MagnetDialogInput::UpdateLabels(labels) => {
+                self.labels = labels;
+            }
             MagnetDialogInput::Submit => {
                 if !self.magnet_link.is_empty() {
                     sender
-                        .output(MagnetDialogOutput::AddMagnet(self.magnet_link.clone()))
+                        .output(MagnetDialogOutput::AddMagnet(
+                            self.magnet_link.clone(),
+                            labels::parse_labels(&self.labels),
+                        ))
                         .unwrap();
                 }
                 self.visible = false;
@@ -8,7 +8,6 @@ use i18n_embed::{
 use relm4::{
     actions::*,
     component::{AsyncComponent, AsyncController},
-    factory::FactoryVecDeque,
     gtk,
     prelude::AsyncComponentController,
     Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmApp,
@@ -16,10 +15,14 @@ use relm4::{
 };
 use relm4_components::open_dialog::*;
 use rust_embed::RustEmbed;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::time::Instant;
 
 mod header;
-use header::{HeaderModel, HeaderOutput};
+use header::{HeaderInput, HeaderModel, HeaderOutput};
 
 mod preferences_window;
 use preferences_window::{PreferencesWindowInput, PreferencesWindowModel};
@@ -27,20 +30,58 @@ use preferences_window::{PreferencesWindowInput, PreferencesWindowModel};
 mod magnet_dialog;
 use magnet_dialog::{MagnetDialogInput, MagnetDialogModel, MagnetDialogOutput};
 
+mod labels_dialog;
+use labels_dialog::{LabelsDialogInput, LabelsDialogModel, LabelsDialogOutput};
+
+mod view_dialog;
+use view_dialog::{ViewDialogInput, ViewDialogModel, ViewDialogOutput};
+
+mod location_dialog;
+use location_dialog::{LocationDialogInput, LocationDialogModel, LocationDialogOutput};
+
+mod details_dialog;
+use details_dialog::{DetailsDialogInput, DetailsDialogModel, DetailsDialogOutput, DetailsPage};
+
+mod background;
 mod file_select_dialog;
-use file_select_dialog::{FileSelectDialogInput, FileSelectDialogModel, FileSelectDialogOutput};
+mod geoip;
+mod labels;
+mod launcher;
+use launcher::LauncherEntry;
+mod notifications;
+mod peers_view;
+mod piece_map;
+use file_select_dialog::{
+    FileSelectDialogInput, FileSelectDialogModel, FileSelectDialogOutput, FileSelection,
+};
+
+mod sidebar;
+use sidebar::{SidebarInput, SidebarModel, TorrentFilter};
+
+mod sort;
+use sort::SortKey;
 
 mod torrent;
-use torrent::Torrent;
+use torrent::{TorrentObject, TorrentOutput};
+
+mod query;
+use query::Query;
+
+mod remove_dialog;
+use remove_dialog::{RemoveDialogInput, RemoveDialogModel, RemoveDialogOutput};
 
 mod toast;
-use toast::{Toast, ToastMsg};
+use toast::{Toast, ToastMsg, ToastOutput};
 
+mod rpc;
 mod transmission;
-use transmission::{Transmission, TransmissionInput, TransmissionOutput};
+use transmission::{QueueMove, Transmission, TransmissionInput, TransmissionOutput};
 
 mod utils;
 
+mod views;
+use views::{SavedView, ViewMatcher};
+
 use transmission_client::TorrentFiles;
 
 #[derive(RustEmbed)]
@@ -64,37 +105,89 @@ macro_rules! fl {
     }};
 }
 
-enum FilterType {
-    All,
-    Downloading,
-    Seeding,
-    Paused,
+/// What's needed to add a removed torrent back if the removal is undone
+#[derive(Debug)]
+struct RemovedTorrent {
+    source: TorrentSource,
+    labels: Vec<String>,
+    download_dir: String,
+}
+
+#[derive(Debug)]
+enum TorrentSource {
+    Metainfo(String), // base64 encoded .torrent file
+    Magnet(String),
 }
 
 struct App {
-    view: FactoryVecDeque<Torrent>,
+    store: gio::ListStore,
+    torrents: HashMap<String, TorrentObject>,
+    filter: gtk::CustomFilter,
+    age_filter_applied: Cell<Option<i64>>, // when an added: search was last applied
+    sorter: gtk::CustomSorter,
+    sort_model: gtk::SortListModel,
+    selection: gtk::MultiSelection,
+    torrent_list: gtk::ListView,
     transmission: AsyncController<Transmission>,
     header: Controller<HeaderModel>,
+    sidebar: Controller<SidebarModel>,
     prefs_dialog: Controller<PreferencesWindowModel>,
     magnet_dialog: Controller<MagnetDialogModel>,
+    location_dialog: Controller<LocationDialogModel>,
+    labels_dialog: Controller<LabelsDialogModel>,
+    view_dialog: Controller<ViewDialogModel>,
     file_select_dialog: Controller<FileSelectDialogModel>,
+    details_dialog: Controller<DetailsDialogModel>,
     open_dialog: Controller<OpenDialog>,
     toast: Controller<Toast>,
+    remove_dialog: Controller<RemoveDialogModel>,
+    removed_torrents: Vec<RemovedTorrent>,
+    torrents_loaded: bool,
+    added_here: HashSet<String>,
+    full_folders: HashSet<String>,
+    disk_space_checked: Option<Instant>,
+    checking_disk_space: bool,
+    background_hold: Option<gio::ApplicationHoldGuard>,
+    launcher_entry: LauncherEntry,
     context_popover: gtk::PopoverMenu,
-    current_filter: FilterType,
-    search_term: String,
+    current_filter: TorrentFilter,
+    current_label: String,
+    label_counts: Vec<(String, usize)>,
+    views: Vec<SavedView>,
+    query: Query,
+    sort_key: SortKey,
+    sort_ascending: bool,
 }
 
 #[derive(Debug)]
 enum AppInput {
-    TorrentsChanged(Vec<transmission_client::Torrent>),
+    TorrentsChanged(Vec<rpc::Torrent>),
     PauseTorrent(String),
+    TorrentAdded(String),
+    DiskSpaceChecked(Vec<(String, i64, u64)>), // folder, bytes left to download, free bytes
+    OpenTorrentData(String),
+    ShowTorrentInFolder(String),
+    OpenFolder(String),
+    PauseFolder(String),
+    WindowHidden,
+    WindowShown,
+    Quit,
     ResumeTorrent(String),
-    GetTorrentFiles(i32),
+    VerifyTorrent(String),
+    ReannounceTorrent(String),
+    ChooseLocation(Vec<String>, String, bool), // hashes, initial folder, move data
+    SetLocationSelected,
+    SetLocation(Vec<String>, String, bool), // hashes, location, move data
     TorrentFileListChanged(TorrentFiles),
+    TrackersLoaded(Vec<rpc::TorrentTrackers>),
+    ShowDetails(DetailsPage),
+    ReannounceSelected,
+    DetailsRequest(TransmissionInput),
+    DetailsChanged(DetailsDialogInput),
 
     ShowOpenDialog,
     OpenTorrent(PathBuf),
+    AddMagnet(String, Vec<String>),
 
     ShowMagnetDialog,
     TriggerFileSelect,
@@ -105,88 +198,165 @@ enum AppInput {
     OpenPrefsWindow,
     ClosePrefsWindow,
     None,
-    RemoveSelected,
+    RemoveSelected(bool), // delete data
+    RemoveTorrents(Vec<String>, bool),
+    UndoRemove,
     PauseSelectedTorrents,
     ResumeSelectedTorrents,
+    VerifySelectedTorrents,
+    StartSelectedNow,
+    MoveSelectedInQueue(QueueMove),
+    QueueDrop(String, String), // dragged hash, hash it was dropped on
     CopySelectedMagnet,
-    UpdateFileSelection(String, i32, Vec<i32>, Vec<i32>),
-    ApplyFilter(u8),
-    UpdateSearch(String),
+    UpdateFileSelection(String, i32, FileSelection),
+    RenameSelected,
+    RenameTorrent(String, i32, String, String),
+    RenamePath(String, i32, String, String),
+    RenamePathFailed(i32, String), // torrent_id, error
+    ApplyFilter(String),           // filter id
+    ApplyLabelFilter(String),
+    EditLabelsSelected,
+    ChangeLabels(Vec<String>, Vec<String>, Vec<String>), // hashes, labels to add, labels to remove
+    SaveView,
+    RenameView(String),
+    NameView(Option<String>, String), // old name if renaming, new name
+    MoveView(String, bool),           // name, up
+    DeleteView(String),
+    SetSortKey(String),
+    SetSortAscending(bool),
+    UpdateSearch(Query),
 }
 
 #[relm4::component]
 impl SimpleComponent for App {
-    type Init = ();
+    type Init = bool; // start in the background without showing the window
     type Input = AppInput;
     type Output = ();
 
     view! {
         main_window = gtk::Window {
             set_default_size: (400, 100),
-            set_titlebar: Some(app.header.widget()),
+            set_titlebar: Some(model.header.widget()),
+
+            connect_close_request[sender] => move |window| {
+                let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+                if settings.boolean("hide-on-close") {
+                    window.set_visible(false);
+                    sender.input(AppInput::WindowHidden);
+                    gtk::glib::Propagation::Stop
+                } else {
+                    gtk::glib::Propagation::Proceed
+                }
+            },
 
             gtk::Overlay {
                 #[name="toplevel_box"]
                 add_overlay = &gtk::Box {
                     set_orientation: gtk::Orientation::Vertical,
 
-                    #[local_ref]
-                    torrent_box -> gtk::ListBox {
-                        set_selection_mode: gtk::SelectionMode::Multiple,
-                        set_activate_on_single_click: false,
-                        add_css_class: granite::STYLE_CLASS_RICH_LIST,
-                        #[wrap(Some)]
-                        set_placeholder = &gtk::Stack {
-                            add_child = &granite::Placeholder {
-                                set_title: &fl!("no-torrents-title"),
-                                set_description: &fl!("no-torrents-subtitle"),
-                                append_button[&fl!("action-open"), &fl!("action-open-description")] = &ThemedIcon::new("folder") {} -> {
-                                    set_action_name: Some(&OpenAction::action_name()),
-                                },
-                                append_button[&fl!("action-prefs"), &fl!("action-prefs-description")] = &ThemedIcon::new("open-menu") {} -> {
-                                    set_action_name: Some(&PreferencesAction::action_name()),
-                                },
+                    gtk::Stack {
+                        #[watch]
+                        set_visible_child_name: if model.torrents.is_empty() { "placeholder" } else { "torrents" },
+
+                        add_named[Some("placeholder")] = &granite::Placeholder {
+                            set_title: &fl!("no-torrents-title"),
+                            set_description: &fl!("no-torrents-subtitle"),
+                            append_button[&fl!("action-open"), &fl!("action-open-description")] = &ThemedIcon::new("folder") {} -> {
+                                set_action_name: Some(&OpenAction::action_name()),
+                            },
+                            append_button[&fl!("action-prefs"), &fl!("action-prefs-description")] = &ThemedIcon::new("open-menu") {} -> {
+                                set_action_name: Some(&PreferencesAction::action_name()),
                             },
                         },
 
-                        add_controller = gtk::GestureClick {
-                            set_button: gtk::gdk::BUTTON_SECONDARY,
-                            connect_released[sender] => move |_, _, x, y| {
-                                sender.input(AppInput::RightClickTorrent(x, y));
-                            }
-                        }
-                    }
+                        add_named[Some("torrents")] = &gtk::Paned {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_shrink_start_child: false,
+                            set_resize_start_child: false,
+                            set_start_child: Some(model.sidebar.widget()),
+
+                            #[wrap(Some)]
+                            set_end_child = &gtk::ScrolledWindow {
+                                set_vexpand: true,
+                                set_hexpand: true,
+                                set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                                #[local_ref]
+                                torrent_list -> gtk::ListView {
+                                    set_enable_rubberband: true,
+                                    add_css_class: granite::STYLE_CLASS_RICH_LIST,
+                                    connect_activate[sender] => move |_, _| {
+                                        sender.input(AppInput::ShowDetails(DetailsPage::General));
+                                    },
+
+                                    add_controller = gtk::GestureClick {
+                                        set_button: gtk::gdk::BUTTON_SECONDARY,
+                                        connect_released[sender] => move |_, _, x, y| {
+                                            sender.input(AppInput::RightClickTorrent(x, y));
+                                        }
+                                    }
+                                }
+                            },
+                        },
+                    },
                 },
 
-                add_overlay = app.toast.widget(),
+                add_overlay = model.toast.widget(),
             }
         }
     }
 
     fn init(
-        _init: Self::Init,
+        background: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         granite::init();
 
-        let seeding_css = gtk::CssProvider::new();
-        seeding_css.load_from_data("progressbar.seeding progress { background-color: @LIME_300; }");
+        let progress_css = gtk::CssProvider::new();
+        progress_css.load_from_data(
+            "progressbar.seeding progress { background-color: @LIME_300; }
+             progressbar.verifying progress { background-color: @BANANA_500; }",
+        );
 
         gtk::style_context_add_provider_for_display(
             &gtk::gdk::Display::default().unwrap(),
-            &seeding_css,
+            &progress_css,
             gtk::STYLE_PROVIDER_PRIORITY_USER,
         );
 
-        let view =
-            FactoryVecDeque::builder()
-                .launch_default()
-                .forward(sender.input_sender(), |msg| match msg {
-                    torrent::TorrentOutput::Pause(hash) => AppInput::PauseTorrent(hash),
-                    torrent::TorrentOutput::Resume(hash) => AppInput::ResumeTorrent(hash),
-                    torrent::TorrentOutput::GetFiles(id) => AppInput::GetTorrentFiles(id),
-                });
+        // Filtering and sorting happen in the models, so only the visible rows have widgets.
+        // Their functions are set by apply_filter and apply_sort.
+        let store = gio::ListStore::new::<TorrentObject>();
+        let filter = gtk::CustomFilter::new(|_| true);
+        let sorter = gtk::CustomSorter::new(|_, _| gtk::Ordering::Equal);
+        let filter_model = gtk::FilterListModel::new(Some(store.clone()), Some(filter.clone()));
+        let sort_model = gtk::SortListModel::new(Some(filter_model), Some(sorter.clone()));
+        let selection = gtk::MultiSelection::new(Some(sort_model.clone()));
+
+        let row_sender = sender.clone();
+        let torrent_list = gtk::ListView::new(
+            Some(selection.clone()),
+            Some(torrent::torrent_factory(move |msg| {
+                row_sender.input(match msg {
+                    TorrentOutput::Pause(hash) => AppInput::PauseTorrent(hash),
+                    TorrentOutput::Resume(hash) => AppInput::ResumeTorrent(hash),
+                    TorrentOutput::Rename(hash, id, old_name, new_name) => {
+                        AppInput::RenameTorrent(hash, id, old_name, new_name)
+                    }
+                    TorrentOutput::Verify(hash) => AppInput::VerifyTorrent(hash),
+                    TorrentOutput::Reannounce(hash) => AppInput::ReannounceTorrent(hash),
+                    TorrentOutput::QueueDrop(dragged_hash, target_hash) => {
+                        AppInput::QueueDrop(dragged_hash, target_hash)
+                    }
+                    TorrentOutput::SetLocation(hash, download_dir) => {
+                        // The data is usually missing from its old location, so default to
+                        // just pointing the torrent at wherever it is now
+                        AppInput::ChooseLocation(vec![hash], download_dir, false)
+                    }
+                })
+            })),
+        );
 
         let transmission =
             Transmission::builder()
@@ -195,6 +365,7 @@ impl SimpleComponent for App {
                     TransmissionOutput::TorrentsChanged(torrents) => {
                         AppInput::TorrentsChanged(torrents)
                     }
+                    TransmissionOutput::TorrentAdded(hash) => AppInput::TorrentAdded(hash),
                     TransmissionOutput::ConnectionError(err) => {
                         println!("Connection error: {}", err);
                         AppInput::None
@@ -202,6 +373,30 @@ impl SimpleComponent for App {
                     TransmissionOutput::FileListChanged(files) => {
                         AppInput::TorrentFileListChanged(files)
                     }
+                    TransmissionOutput::DetailsFilesChanged(files) => {
+                        AppInput::DetailsChanged(DetailsDialogInput::FilesChanged(files))
+                    }
+                    TransmissionOutput::TorrentDetailsChanged(torrent) => {
+                        AppInput::DetailsChanged(DetailsDialogInput::GeneralChanged(torrent))
+                    }
+                    TransmissionOutput::TrackersChanged(trackers) => {
+                        AppInput::DetailsChanged(DetailsDialogInput::TrackersChanged(trackers))
+                    }
+                    TransmissionOutput::AllTrackersChanged(trackers) => {
+                        AppInput::TrackersLoaded(trackers)
+                    }
+                    TransmissionOutput::PeersChanged(peers) => {
+                        AppInput::DetailsChanged(DetailsDialogInput::PeersChanged(peers))
+                    }
+                    TransmissionOutput::PiecesChanged(pieces) => {
+                        AppInput::DetailsChanged(DetailsDialogInput::PiecesChanged(pieces))
+                    }
+                    TransmissionOutput::TrackerEditFailed(err) => {
+                        AppInput::DetailsChanged(DetailsDialogInput::TrackerEditFailed(err))
+                    }
+                    TransmissionOutput::RenamePathFailed(torrent_id, err) => {
+                        AppInput::RenamePathFailed(torrent_id, err)
+                    }
                 });
 
         let header = HeaderModel::builder()
@@ -209,7 +404,7 @@ impl SimpleComponent for App {
             .forward(sender.input_sender(), |msg| match msg {
                 HeaderOutput::OpenTorrent => AppInput::ShowOpenDialog,
                 HeaderOutput::OpenMagnet => AppInput::ShowMagnetDialog,
-                HeaderOutput::SearchChanged(search_term) => AppInput::UpdateSearch(search_term),
+                HeaderOutput::SearchChanged(query) => AppInput::UpdateSearch(query),
             });
 
         let open_dialog = OpenDialog::builder()
@@ -234,22 +429,92 @@ impl SimpleComponent for App {
             .transient_for(&root)
             .launch(())
             .forward(sender.input_sender(), |msg| match msg {
-                MagnetDialogOutput::AddMagnet(link) => AppInput::OpenTorrent(PathBuf::from(link)),
+                MagnetDialogOutput::AddMagnet(link, labels) => AppInput::AddMagnet(link, labels),
                 MagnetDialogOutput::Close => AppInput::None,
             });
 
+        let location_dialog = LocationDialogModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), |msg| match msg {
+                LocationDialogOutput::SetLocation(hashes, location, move_data) => {
+                    AppInput::SetLocation(hashes, location, move_data)
+                }
+            });
+
+        let labels_dialog = LabelsDialogModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), |msg| match msg {
+                LabelsDialogOutput::ChangeLabels(hashes, added, removed) => {
+                    AppInput::ChangeLabels(hashes, added, removed)
+                }
+            });
+
+        let view_dialog = ViewDialogModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), |msg| match msg {
+                ViewDialogOutput::NameView(old_name, name) => AppInput::NameView(old_name, name),
+            });
+
         let file_select_dialog = FileSelectDialogModel::builder()
             .transient_for(&root)
             .launch(())
             .forward(sender.input_sender(), |msg| match msg {
-                FileSelectDialogOutput::UpdateFiles(hash, torrent_id, wanted, unwanted) => {
-                    AppInput::UpdateFileSelection(hash, torrent_id, wanted, unwanted)
+                FileSelectDialogOutput::UpdateFiles(hash, torrent_id, selection) => {
+                    AppInput::UpdateFileSelection(hash, torrent_id, selection)
+                }
+                FileSelectDialogOutput::RenamePath(hash, torrent_id, path, new_name) => {
+                    AppInput::RenamePath(hash, torrent_id, path, new_name)
+                }
+            });
+
+        let details_dialog = DetailsDialogModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), |msg| match msg {
+                DetailsDialogOutput::GetGeneral(id) => {
+                    AppInput::DetailsRequest(TransmissionInput::GetTorrentDetails(id))
+                }
+                DetailsDialogOutput::GetTrackers(id) => {
+                    AppInput::DetailsRequest(TransmissionInput::GetTrackers(id))
+                }
+                DetailsDialogOutput::GetPeers(id) => {
+                    AppInput::DetailsRequest(TransmissionInput::GetPeers(id))
+                }
+                DetailsDialogOutput::GetPieces(id) => {
+                    AppInput::DetailsRequest(TransmissionInput::GetPieces(id))
+                }
+                DetailsDialogOutput::GetFiles(id) => {
+                    AppInput::DetailsRequest(TransmissionInput::GetDetailsFiles(id))
+                }
+                DetailsDialogOutput::SetTrackerList(hash, id, tracker_list) => {
+                    AppInput::DetailsRequest(TransmissionInput::SetTrackerList(
+                        hash,
+                        id,
+                        tracker_list,
+                    ))
+                }
+                DetailsDialogOutput::Reannounce(hash) => {
+                    AppInput::DetailsRequest(TransmissionInput::ReannounceTorrents(vec![hash]))
                 }
             });
 
         let toast = Toast::builder()
             .launch(())
-            .forward(sender.input_sender(), |response| match response {});
+            .forward(sender.input_sender(), |response| match response {
+                ToastOutput::Action => AppInput::UndoRemove,
+            });
+
+        let remove_dialog = RemoveDialogModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), |msg| match msg {
+                RemoveDialogOutput::Remove(hashes, delete_data) => {
+                    AppInput::RemoveTorrents(hashes, delete_data)
+                }
+            });
 
         let context_popover = gtk::PopoverMenu::builder()
             .halign(gtk::Align::Start)
@@ -257,24 +522,57 @@ impl SimpleComponent for App {
             .position(gtk::PositionType::Bottom)
             .build();
 
-        let app = App {
-            view,
+        let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+        let sort_by = settings.string("sort-by").to_string();
+        let sort_ascending = settings.boolean("sort-ascending");
+        let filter_id = settings.string("filter").to_string();
+
+        let sidebar = SidebarModel::builder().launch(filter_id.clone()).detach();
+
+        let model = App {
+            store,
+            torrents: HashMap::new(),
+            filter,
+            age_filter_applied: Cell::new(None),
+            sorter,
+            sort_model,
+            selection,
+            torrent_list: torrent_list.clone(),
             header,
+            sidebar,
             transmission,
             prefs_dialog,
             magnet_dialog,
+            location_dialog,
+            labels_dialog,
+            view_dialog,
             file_select_dialog,
+            details_dialog,
             open_dialog,
             context_popover,
             toast,
-            current_filter: FilterType::All,
-            search_term: String::new(),
+            remove_dialog,
+            removed_torrents: vec![],
+            torrents_loaded: false,
+            added_here: HashSet::new(),
+            full_folders: HashSet::new(),
+            disk_space_checked: None,
+            checking_disk_space: false,
+            background_hold: None,
+            launcher_entry: LauncherEntry::default(),
+            current_filter: TorrentFilter::from_id(&filter_id),
+            current_label: String::new(),
+            label_counts: vec![],
+            views: views::saved_views(),
+            query: Query::default(),
+            sort_key: SortKey::from_id(&sort_by),
+            sort_ascending,
         };
-
-        let torrent_box = app.view.widget();
+        model.apply_filter();
+        model.apply_sort();
 
         let widgets = view_output!();
-        app.context_popover.set_parent(&widgets.toplevel_box);
+        model.context_popover.set_parent(&widgets.toplevel_box);
 
         let prefs_sender = sender.clone();
         let preferences_action: RelmAction<PreferencesAction> =
@@ -282,6 +580,11 @@ impl SimpleComponent for App {
                 prefs_sender.input(AppInput::OpenPrefsWindow);
             });
 
+        let quit_sender = sender.clone();
+        let quit_action: RelmAction<QuitAction> = RelmAction::new_stateless(move |_| {
+            quit_sender.input(AppInput::Quit);
+        });
+
         let open_sender = sender.clone();
         let open_action: RelmAction<OpenAction> = RelmAction::new_stateless(move |_| {
             open_sender.input(AppInput::ShowOpenDialog);
@@ -290,7 +593,13 @@ impl SimpleComponent for App {
         let remove_sender = sender.clone();
         let remove_selected_action: RelmAction<RemoveSelectedAction> =
             RelmAction::new_stateless(move |_| {
-                remove_sender.input(AppInput::RemoveSelected);
+                remove_sender.input(AppInput::RemoveSelected(false));
+            });
+
+        let remove_and_delete_sender = sender.clone();
+        let remove_and_delete_selected_action: RelmAction<RemoveAndDeleteSelectedAction> =
+            RelmAction::new_stateless(move |_| {
+                remove_and_delete_sender.input(AppInput::RemoveSelected(true));
             });
 
         let pause_sender = sender.clone();
@@ -305,6 +614,41 @@ impl SimpleComponent for App {
                 resume_sender.input(AppInput::ResumeSelectedTorrents);
             });
 
+        let verify_sender = sender.clone();
+        let verify_selected_action: RelmAction<VerifySelectedAction> =
+            RelmAction::new_stateless(move |_| {
+                verify_sender.input(AppInput::VerifySelectedTorrents);
+            });
+
+        let start_now_sender = sender.clone();
+        let start_now_action: RelmAction<StartNowAction> = RelmAction::new_stateless(move |_| {
+            start_now_sender.input(AppInput::StartSelectedNow);
+        });
+
+        let queue_top_sender = sender.clone();
+        let queue_move_top_action: RelmAction<QueueMoveTopAction> =
+            RelmAction::new_stateless(move |_| {
+                queue_top_sender.input(AppInput::MoveSelectedInQueue(QueueMove::Top));
+            });
+
+        let queue_up_sender = sender.clone();
+        let queue_move_up_action: RelmAction<QueueMoveUpAction> =
+            RelmAction::new_stateless(move |_| {
+                queue_up_sender.input(AppInput::MoveSelectedInQueue(QueueMove::Up));
+            });
+
+        let queue_down_sender = sender.clone();
+        let queue_move_down_action: RelmAction<QueueMoveDownAction> =
+            RelmAction::new_stateless(move |_| {
+                queue_down_sender.input(AppInput::MoveSelectedInQueue(QueueMove::Down));
+            });
+
+        let queue_bottom_sender = sender.clone();
+        let queue_move_bottom_action: RelmAction<QueueMoveBottomAction> =
+            RelmAction::new_stateless(move |_| {
+                queue_bottom_sender.input(AppInput::MoveSelectedInQueue(QueueMove::Bottom));
+            });
+
         let copy_magnet_sender = sender.clone();
         let copy_magnet_action: RelmAction<CopySelectedMagnetAction> =
             RelmAction::new_stateless(move |_| {
@@ -317,6 +661,41 @@ impl SimpleComponent for App {
                 file_select_sender.input(AppInput::TriggerFileSelect);
             });
 
+        let rename_sender = sender.clone();
+        let rename_selected_action: RelmAction<RenameSelectedAction> =
+            RelmAction::new_stateless(move |_| {
+                rename_sender.input(AppInput::RenameSelected);
+            });
+
+        let details_sender = sender.clone();
+        let details_action: RelmAction<DetailsAction> = RelmAction::new_stateless(move |_| {
+            details_sender.input(AppInput::ShowDetails(DetailsPage::General));
+        });
+
+        let edit_trackers_sender = sender.clone();
+        let edit_trackers_action: RelmAction<EditTrackersAction> =
+            RelmAction::new_stateless(move |_| {
+                edit_trackers_sender.input(AppInput::ShowDetails(DetailsPage::Trackers));
+            });
+
+        let reannounce_sender = sender.clone();
+        let reannounce_selected_action: RelmAction<ReannounceSelectedAction> =
+            RelmAction::new_stateless(move |_| {
+                reannounce_sender.input(AppInput::ReannounceSelected);
+            });
+
+        let set_location_sender = sender.clone();
+        let set_location_action: RelmAction<SetLocationAction> =
+            RelmAction::new_stateless(move |_| {
+                set_location_sender.input(AppInput::SetLocationSelected);
+            });
+
+        let edit_labels_sender = sender.clone();
+        let edit_labels_action: RelmAction<EditLabelsAction> =
+            RelmAction::new_stateless(move |_| {
+                edit_labels_sender.input(AppInput::EditLabelsSelected);
+            });
+
         let show_in_filemanager_sender = sender.clone();
         let show_in_filemanager_action: RelmAction<ShowInFileManagerAction> =
             RelmAction::new_stateless(move |_| {
@@ -325,121 +704,516 @@ impl SimpleComponent for App {
 
         let filter_action_sender = sender.clone();
         let filter_action: RelmAction<FilterAction> =
-            RelmAction::new_stateful_with_target_value(&0, move |_, state, value| {
-                *state = value;
+            RelmAction::new_stateful_with_target_value(&filter_id, move |_, state, value| {
+                state.clone_from(&value);
                 filter_action_sender.input(AppInput::ApplyFilter(value));
             });
 
+        let label_filter_sender = sender.clone();
+        let label_filter_action: RelmAction<LabelFilterAction> =
+            RelmAction::new_stateful_with_target_value(&String::new(), move |_, state, value| {
+                state.clone_from(&value);
+                label_filter_sender.input(AppInput::ApplyLabelFilter(value));
+            });
+
+        let save_view_sender = sender.clone();
+        let save_view_action: RelmAction<SaveViewAction> = RelmAction::new_stateless(move |_| {
+            save_view_sender.input(AppInput::SaveView);
+        });
+
+        let rename_view_sender = sender.clone();
+        let rename_view_action: RelmAction<RenameViewAction> =
+            RelmAction::new_with_target_value(move |_, name| {
+                rename_view_sender.input(AppInput::RenameView(name));
+            });
+
+        let move_view_up_sender = sender.clone();
+        let move_view_up_action: RelmAction<MoveViewUpAction> =
+            RelmAction::new_with_target_value(move |_, name| {
+                move_view_up_sender.input(AppInput::MoveView(name, true));
+            });
+
+        let move_view_down_sender = sender.clone();
+        let move_view_down_action: RelmAction<MoveViewDownAction> =
+            RelmAction::new_with_target_value(move |_, name| {
+                move_view_down_sender.input(AppInput::MoveView(name, false));
+            });
+
+        let delete_view_sender = sender.clone();
+        let delete_view_action: RelmAction<DeleteViewAction> =
+            RelmAction::new_with_target_value(move |_, name| {
+                delete_view_sender.input(AppInput::DeleteView(name));
+            });
+
+        let sort_by_sender = sender.clone();
+        let sort_by_action: RelmAction<SortByAction> =
+            RelmAction::new_stateful_with_target_value(&sort_by, move |_, state, value| {
+                state.clone_from(&value);
+                sort_by_sender.input(AppInput::SetSortKey(value));
+            });
+
+        let sort_ascending_sender = sender.clone();
+        let sort_ascending_action: RelmAction<SortAscendingAction> =
+            RelmAction::new_stateful(&sort_ascending, move |_, state| {
+                *state = !*state;
+                sort_ascending_sender.input(AppInput::SetSortAscending(*state));
+            });
+
         let mut group = RelmActionGroup::<WindowActionGroup>::new();
         group.add_action(preferences_action);
         group.add_action(open_action);
         group.add_action(pause_selected_action);
         group.add_action(resume_selected_action);
+        group.add_action(verify_selected_action);
+        group.add_action(start_now_action);
+        group.add_action(queue_move_top_action);
+        group.add_action(queue_move_up_action);
+        group.add_action(queue_move_down_action);
+        group.add_action(queue_move_bottom_action);
         group.add_action(remove_selected_action);
+        group.add_action(remove_and_delete_selected_action);
         group.add_action(copy_magnet_action);
         group.add_action(file_select_action);
+        group.add_action(rename_selected_action);
+        group.add_action(details_action);
+        group.add_action(edit_trackers_action);
+        group.add_action(reannounce_selected_action);
+        group.add_action(set_location_action);
+        group.add_action(edit_labels_action);
         group.add_action(show_in_filemanager_action);
         group.add_action(filter_action);
+        group.add_action(label_filter_action);
+        group.add_action(save_view_action);
+        group.add_action(rename_view_action);
+        group.add_action(move_view_up_action);
+        group.add_action(move_view_down_action);
+        group.add_action(delete_view_action);
+        group.add_action(sort_by_action);
+        group.add_action(sort_ascending_action);
         group.register_for_widget(&widgets.main_window);
 
-        widgets.load_window_size();
+        // Notification buttons can only activate actions on the application
+        let open_data_sender = sender.clone();
+        let open_torrent_data_action: RelmAction<OpenTorrentDataAction> =
+            RelmAction::new_with_target_value(move |_, hash| {
+                open_data_sender.input(AppInput::OpenTorrentData(hash));
+            });
+
+        let show_in_folder_sender = sender.clone();
+        let show_torrent_in_folder_action: RelmAction<ShowTorrentInFolderAction> =
+            RelmAction::new_with_target_value(move |_, hash| {
+                show_in_folder_sender.input(AppInput::ShowTorrentInFolder(hash));
+            });
+
+        let pause_torrent_sender = sender.clone();
+        let pause_torrent_action: RelmAction<PauseTorrentAction> =
+            RelmAction::new_with_target_value(move |_, hash| {
+                pause_torrent_sender.input(AppInput::PauseTorrent(hash));
+            });
+
+        let open_folder_sender = sender.clone();
+        let open_folder_action: RelmAction<OpenFolderAction> =
+            RelmAction::new_with_target_value(move |_, folder| {
+                open_folder_sender.input(AppInput::OpenFolder(folder));
+            });
 
-        ComponentParts {
-            model: app,
-            widgets,
+        let pause_folder_sender = sender.clone();
+        let pause_folder_action: RelmAction<PauseFolderAction> =
+            RelmAction::new_with_target_value(move |_, folder| {
+                pause_folder_sender.input(AppInput::PauseFolder(folder));
+            });
+
+        let mut app_group = RelmActionGroup::<AppActionGroup>::new();
+        app_group.add_action(open_torrent_data_action);
+        app_group.add_action(show_torrent_in_folder_action);
+        app_group.add_action(pause_torrent_action);
+        app_group.add_action(open_folder_action);
+        app_group.add_action(pause_folder_action);
+        app_group.add_action(quit_action);
+        app_group.register_for_main_application();
+
+        // Launching the app again while it's running in the background activates this
+        // instance, which should bring back the hidden window
+        let main_window = widgets.main_window.clone();
+        let activate_sender = sender.clone();
+        relm4::main_application().connect_activate(move |_| {
+            main_window.present();
+            activate_sender.input(AppInput::WindowShown);
+        });
+
+        if background {
+            // The window is shown as soon as we return, so hide it again before it's drawn
+            let main_window = widgets.main_window.clone();
+            let hide_sender = sender.clone();
+            gtk::glib::idle_add_local_full(gtk::glib::Priority::HIGH, move || {
+                main_window.set_visible(false);
+                hide_sender.input(AppInput::WindowHidden);
+                gtk::glib::ControlFlow::Break
+            });
         }
+
+        widgets.load_window_size();
+
+        ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
         match message {
             AppInput::TorrentsChanged(new_torrents) => {
-                let mut guarded_view = self.view.guard();
-                let new_torrents_len = new_torrents.len();
-
-                for (index, torrent) in new_torrents.into_iter().enumerate() {
-                    if index >= guarded_view.len() {
-                        guarded_view.push_back(torrent);
-                        continue;
-                    }
-
-                    if let Some(torrent_container) = guarded_view.get_mut(index) {
-                        torrent_container.update(&torrent);
-                    }
+                let hashes: HashSet<&str> = new_torrents
+                    .iter()
+                    .map(|torrent| torrent.hash_string.as_str())
+                    .collect();
+                self.torrents
+                    .retain(|hash, _| hashes.contains(hash.as_str()));
+                if self.store.n_items() as usize != self.torrents.len() {
+                    self.store.retain(|item| {
+                        item.downcast_ref::<TorrentObject>()
+                            .is_some_and(|torrent| self.torrents.contains_key(&torrent.hash()))
+                    });
                 }
 
-                if guarded_view.len() > new_torrents_len {
-                    for _ in 0..(guarded_view.len() - new_torrents_len) {
-                        guarded_view.pop_back();
+                let mut added = vec![];
+                let mut refilter = false;
+                let mut resort = false;
+                for torrent in new_torrents {
+                    match self.torrents.get(&torrent.hash_string) {
+                        Some(existing) => {
+                            let changes =
+                                notifications::torrent_changes(&existing.data(), &torrent);
+
+                            // The models only need to look again if this torrent moved in or
+                            // out of the list, or to another place in it
+                            let data_changed = *existing.data() != torrent;
+                            resort |= data_changed
+                                && self.sort_key.compare(&existing.data(), &torrent)
+                                    != Ordering::Equal;
+                            let was_shown = data_changed && self.filter.match_(existing);
+                            existing.update(torrent);
+                            refilter |= data_changed && self.filter.match_(existing) != was_shown;
+
+                            for kind in changes {
+                                notifications::notify_torrent(kind, existing);
+                            }
+                        }
+                        None => {
+                            self.transmission
+                                .emit(TransmissionInput::GetFiles(torrent.id));
+                            let torrent = TorrentObject::new(torrent);
+                            self.torrents.insert(torrent.hash(), torrent.clone());
+
+                            // Torrents already there on launch aren't news, and ones added
+                            // through this window don't need pointing out
+                            if self.torrents_loaded && !self.added_here.remove(&torrent.hash()) {
+                                notifications::notify_torrent(
+                                    notifications::NotificationKind::Added,
+                                    &torrent,
+                                );
+                            }
+                            added.push(torrent);
+                        }
                     }
                 }
+                self.torrents_loaded = true;
+                self.check_disk_space(&sender);
+
+                let launcher_entry =
+                    LauncherEntry::new(self.torrents.values().map(TorrentObject::data));
+                if launcher_entry != self.launcher_entry {
+                    launcher_entry.send();
+                    self.launcher_entry = launcher_entry;
+                }
+                if !added.is_empty() {
+                    self.transmission.emit(TransmissionInput::GetAllTrackers);
+                }
+                self.store.extend_from_slice(&added);
+
+                let label_counts =
+                    labels::label_counts(self.torrents.values().map(|torrent| torrent.labels()));
+                if label_counts != self.label_counts {
+                    self.header
+                        .emit(HeaderInput::LabelsChanged(label_counts.clone()));
+                    self.label_counts = label_counts;
+                }
 
-                drop(guarded_view);
-                self.apply_filter();
+                self.sidebar.emit(SidebarInput::TorrentsChanged(
+                    self.torrents.values().cloned().collect(),
+                ));
+
+                // The rows' data changed in place, so the models have to be told to look again
+                // Torrents can also age out of an added: search without anything changing
+                let now = gtk::glib::DateTime::now_utc()
+                    .map(|now| now.to_unix())
+                    .unwrap_or_default();
+                if self
+                    .age_filter_applied
+                    .get()
+                    .is_some_and(|applied| now - applied >= 60)
+                {
+                    self.apply_filter();
+                } else if refilter {
+                    self.filter.changed(gtk::FilterChange::Different);
+                }
+                if resort {
+                    self.apply_sort();
+                }
+                self.details_dialog.emit(DetailsDialogInput::Refresh);
             }
             AppInput::PauseTorrent(hash) => self
                 .transmission
                 .emit(TransmissionInput::PauseTorrents(vec![hash])),
-            AppInput::PauseSelectedTorrents => {
-                let mut hashes = vec![];
-                let items = self.view.guard().widget().selected_rows();
-                for item in items {
-                    if let Some(torrent) = self.view.guard().get(item.index() as usize) {
-                        hashes.push(torrent.hash.clone());
+            AppInput::TorrentAdded(hash) => {
+                self.added_here.insert(hash);
+            }
+            AppInput::DiskSpaceChecked(folders) => {
+                self.checking_disk_space = false;
+                for (folder, remaining, free) in folders {
+                    if remaining > 0 && notifications::disk_nearly_full(free, remaining as u64) {
+                        if self.full_folders.insert(folder.clone()) {
+                            notifications::notify_disk_space(&folder, free);
+                        }
+                    } else {
+                        self.full_folders.remove(&folder);
                     }
                 }
-
+            }
+            AppInput::OpenTorrentData(hash) => {
+                if let Some(torrent) = self.torrents.get(&hash) {
+                    utils::open_torrent_file(&torrent.download_dir(), &torrent.name());
+                }
+            }
+            AppInput::ShowTorrentInFolder(hash) => {
+                if let Some(torrent) = self.torrents.get(&hash) {
+                    utils::open_torrent_location(&torrent.download_dir(), &torrent.name());
+                }
+            }
+            AppInput::OpenFolder(folder) => {
+                utils::open_torrent_file(&folder, "");
+            }
+            AppInput::PauseFolder(folder) => {
+                let hashes = self
+                    .torrents_downloading_to(&folder)
+                    .map(|torrent| torrent.hash())
+                    .collect();
+                self.transmission
+                    .emit(TransmissionInput::PauseTorrents(hashes));
+            }
+            AppInput::WindowHidden => {
+                // Keep the app, and with it the daemon, running while there's no window on screen
+                if self.background_hold.is_none() {
+                    background::request_background();
+                    notifications::notify_running_in_background();
+                    self.background_hold = Some(relm4::main_application().hold());
+                }
+            }
+            AppInput::WindowShown => {
+                notifications::withdraw_running_in_background();
+                self.background_hold = None;
+            }
+            AppInput::Quit => {
+                notifications::withdraw_running_in_background();
+                self.background_hold = None;
+                relm4::main_application().quit();
+            }
+            AppInput::PauseSelectedTorrents => {
+                let hashes = self.selected_hashes();
                 self.transmission
                     .emit(TransmissionInput::PauseTorrents(hashes));
             }
             AppInput::ResumeTorrent(hash) => self
                 .transmission
                 .emit(TransmissionInput::ResumeTorrents(vec![hash])),
-            AppInput::ResumeSelectedTorrents => {
-                let mut hashes = vec![];
-                let items = self.view.guard().widget().selected_rows();
-                for item in items {
-                    if let Some(torrent) = self.view.guard().get(item.index() as usize) {
-                        hashes.push(torrent.hash.clone());
+            AppInput::VerifySelectedTorrents => {
+                let hashes = self.selected_hashes();
+                self.transmission
+                    .emit(TransmissionInput::VerifyTorrents(hashes));
+            }
+            AppInput::StartSelectedNow => {
+                let hashes = self.selected_hashes();
+                self.transmission
+                    .emit(TransmissionInput::StartTorrentsNow(hashes));
+            }
+            AppInput::MoveSelectedInQueue(queue_move) => {
+                let hashes = self.selected_hashes();
+                self.transmission
+                    .emit(TransmissionInput::MoveInQueue(hashes, queue_move));
+            }
+            AppInput::QueueDrop(dragged_hash, target_hash) => {
+                // The dragged torrent takes the place of the one it was dropped on
+                let position = self
+                    .torrents
+                    .get(&target_hash)
+                    .map(|torrent| torrent.queue_position());
+
+                if let Some(position) = position {
+                    self.transmission
+                        .emit(TransmissionInput::SetQueuePosition(dragged_hash, position));
+                }
+            }
+            AppInput::VerifyTorrent(hash) => self
+                .transmission
+                .emit(TransmissionInput::VerifyTorrents(vec![hash])),
+            AppInput::ReannounceTorrent(hash) => self
+                .transmission
+                .emit(TransmissionInput::ReannounceTorrents(vec![hash])),
+            AppInput::ChooseLocation(hashes, initial_folder, move_data) => {
+                self.location_dialog.emit(LocationDialogInput::Open(
+                    hashes,
+                    initial_folder,
+                    move_data,
+                ));
+            }
+            AppInput::SetLocationSelected => {
+                let selected = self.selected_torrents();
+                if let Some(first) = selected.first() {
+                    self.location_dialog.emit(LocationDialogInput::Open(
+                        selected.iter().map(|torrent| torrent.hash()).collect(),
+                        first.download_dir(),
+                        true,
+                    ));
+                }
+            }
+            AppInput::SetLocation(hashes, location, move_data) => {
+                // Moving can take a while, so show it on the rows until the daemon is done
+                if move_data {
+                    for hash in &hashes {
+                        if let Some(torrent) = self.torrents.get(hash) {
+                            torrent.start_move(location.clone());
+                        }
                     }
                 }
 
+                self.transmission
+                    .emit(TransmissionInput::SetLocation(hashes, location, move_data));
+            }
+            AppInput::ResumeSelectedTorrents => {
+                let hashes = self.selected_hashes();
                 self.transmission
                     .emit(TransmissionInput::ResumeTorrents(hashes));
             }
-            AppInput::RemoveSelected => {
-                let mut hashes = vec![];
-                let items = self.view.guard().widget().selected_rows();
-                for item in items {
-                    if let Some(torrent) = self.view.guard().get(item.index() as usize) {
-                        hashes.push(torrent.hash.clone());
-                    }
-                }
+            AppInput::RemoveSelected(delete_data) => {
+                let selected = self.selected_torrents();
+                let total_size = selected.iter().map(|torrent| torrent.size_on_disk()).sum();
+                let torrents = selected
+                    .iter()
+                    .map(|torrent| (torrent.hash(), torrent.name()))
+                    .collect();
+
+                self.remove_dialog
+                    .emit(RemoveDialogInput::Open(torrents, total_size, delete_data));
+            }
+            AppInput::RemoveTorrents(hashes, delete_data) => {
+                // Keep enough to add the torrents back, as the daemon deletes its copy of the
+                // .torrent file on removal
+                self.removed_torrents = if delete_data {
+                    vec![]
+                } else {
+                    hashes
+                        .iter()
+                        .filter_map(|hash| self.torrents.get(hash))
+                        .map(|torrent| RemovedTorrent {
+                            source: match std::fs::read(torrent.torrent_file()) {
+                                Ok(metainfo) => TorrentSource::Metainfo(
+                                    gtk::glib::base64_encode(&metainfo).into(),
+                                ),
+                                Err(_) => TorrentSource::Magnet(torrent.magnet_link()),
+                            },
+                            labels: torrent.labels(),
+                            download_dir: torrent.download_dir(),
+                        })
+                        .collect()
+                };
 
+                let count = hashes.len();
                 self.transmission
-                    .emit(TransmissionInput::RemoveTorrents(hashes));
+                    .emit(TransmissionInput::RemoveTorrents(hashes, delete_data));
+
+                if !delete_data {
+                    self.toast.emit(ToastMsg::ShowWithAction(
+                        fl!("torrents-removed-notification", count = count),
+                        fl!("action-undo"),
+                    ));
+                }
             }
-            AppInput::CopySelectedMagnet => {
-                let items = self.view.guard().widget().selected_rows();
-                if items.len() == 1 {
-                    if let Some(torrent) = self.view.guard().get(items[0].index() as usize) {
-                        let clipboard = gtk::gdk::Display::default().unwrap().clipboard();
-                        clipboard.set_text(&torrent.magnet_link);
-                        self.toast
-                            .emit(ToastMsg::Show(fl!("magnet-copied-notification")));
-                    }
+            AppInput::UndoRemove => {
+                for removed in std::mem::take(&mut self.removed_torrents) {
+                    // Put the torrent back where its data is, rather than in the default folder
+                    let download_dir = Some(removed.download_dir);
+                    self.transmission.emit(match removed.source {
+                        TorrentSource::Metainfo(metainfo) => TransmissionInput::AddTorrentMetainfo(
+                            metainfo,
+                            removed.labels,
+                            download_dir,
+                        ),
+                        TorrentSource::Magnet(magnet) => {
+                            TransmissionInput::AddTorrentFile(magnet, removed.labels, download_dir)
+                        }
+                    });
                 }
             }
-            AppInput::GetTorrentFiles(id) => {
-                self.transmission.emit(TransmissionInput::GetFiles(id))
+            AppInput::CopySelectedMagnet => {
+                if let [torrent] = self.selected_torrents().as_slice() {
+                    let clipboard = gtk::gdk::Display::default().unwrap().clipboard();
+                    clipboard.set_text(&torrent.magnet_link());
+                    self.toast
+                        .emit(ToastMsg::Show(fl!("magnet-copied-notification")));
+                }
             }
             AppInput::TorrentFileListChanged(files) => {
-                let mut guarded_view = self.view.guard();
-                for torrent in guarded_view.iter_mut() {
-                    if torrent.id == files.id {
-                        torrent.set_files(files);
-                        break;
+                if let Some(torrent) = self
+                    .torrents
+                    .values()
+                    .find(|torrent| torrent.id() == files.id)
+                {
+                    self.file_select_dialog
+                        .emit(FileSelectDialogInput::FilesChanged(
+                            torrent.name(),
+                            files.clone(),
+                        ));
+                    torrent.set_files(files);
+                }
+            }
+            AppInput::TrackersLoaded(all_trackers) => {
+                let torrents_by_id: HashMap<i32, &TorrentObject> = self
+                    .torrents
+                    .values()
+                    .map(|torrent| (torrent.id(), torrent))
+                    .collect();
+                for trackers in all_trackers {
+                    if let Some(torrent) = torrents_by_id.get(&trackers.id) {
+                        let mut domains: Vec<String> = trackers
+                            .tracker_stats
+                            .iter()
+                            .filter_map(|tracker| sidebar::tracker_domain(&tracker.announce))
+                            .collect();
+                        domains.sort();
+                        domains.dedup();
+                        torrent.set_tracker_domains(domains);
                     }
                 }
+
+                self.apply_filter();
+            }
+            AppInput::ShowDetails(page) => {
+                if let [torrent] = self.selected_torrents().as_slice() {
+                    self.details_dialog.emit(DetailsDialogInput::Open(
+                        torrent.hash(),
+                        torrent.id(),
+                        torrent.name(),
+                        page,
+                    ));
+                }
+            }
+            AppInput::ReannounceSelected => {
+                let hashes = self.selected_hashes();
+                self.transmission
+                    .emit(TransmissionInput::ReannounceTorrents(hashes));
+            }
+            AppInput::DetailsRequest(request) => {
+                self.transmission.emit(request);
+            }
+            AppInput::DetailsChanged(details) => {
+                self.details_dialog.emit(details);
             }
             AppInput::ShowOpenDialog => {
                 self.open_dialog.emit(OpenDialogMsg::Open);
@@ -447,30 +1221,61 @@ impl SimpleComponent for App {
             AppInput::OpenTorrent(path) => {
                 self.transmission.emit(TransmissionInput::AddTorrentFile(
                     path.to_string_lossy().to_string(),
+                    vec![],
+                    None,
+                ));
+            }
+            AppInput::AddMagnet(link, labels) => {
+                let download_dir = labels::folder_for_labels(&labels);
+                self.transmission.emit(TransmissionInput::AddTorrentFile(
+                    link,
+                    labels,
+                    download_dir,
                 ));
             }
             AppInput::ShowMagnetDialog => {
                 self.magnet_dialog.emit(MagnetDialogInput::Open);
             }
             AppInput::TriggerFileSelect => {
-                // Get the selected torrent's information
-                let items = self.view.guard().widget().selected_rows();
-                if items.len() == 1 {
-                    if let Some(torrent) = self.view.guard().get(items[0].index() as usize) {
-                        self.file_select_dialog.emit(FileSelectDialogInput::Open(
-                            torrent.hash.clone(),
-                            torrent.id,
-                            torrent.name.clone(),
-                            torrent.files.clone(),
-                        ));
-                    }
+                if let [torrent] = self.selected_torrents().as_slice() {
+                    self.file_select_dialog.emit(FileSelectDialogInput::Open(
+                        torrent.hash(),
+                        torrent.id(),
+                        torrent.name(),
+                        torrent.download_dir(),
+                        torrent.files(),
+                    ));
+                }
+            }
+            AppInput::UpdateFileSelection(hash, torrent_id, selection) => {
+                self.transmission.emit(TransmissionInput::SetFileSelection(
+                    hash, torrent_id, selection,
+                ));
+            }
+            AppInput::RenameSelected => {
+                if let [torrent] = self.selected_torrents().as_slice() {
+                    torrent.set_renaming(true);
+                }
+            }
+            AppInput::RenameTorrent(hash, torrent_id, old_name, new_name) => {
+                match utils::validate_new_name(&new_name, &[]) {
+                    Ok(_) => self.transmission.emit(TransmissionInput::RenamePath(
+                        hash, torrent_id, old_name, new_name,
+                    )),
+                    Err(err) => self.toast.emit(ToastMsg::Show(err.message())),
                 }
             }
-            AppInput::UpdateFileSelection(hash, torrent_id, wanted, unwanted) => {
-                self.transmission.emit(TransmissionInput::SetFilesWanted(
-                    hash, torrent_id, wanted, unwanted,
+            AppInput::RenamePath(hash, torrent_id, path, new_name) => {
+                self.transmission.emit(TransmissionInput::RenamePath(
+                    hash, torrent_id, path, new_name,
                 ));
             }
+            AppInput::RenamePathFailed(torrent_id, err) => {
+                let message = fl!("rename-error-failed", error = err.clone());
+                self.toast.emit(ToastMsg::Show(message));
+                self.file_select_dialog
+                    .emit(FileSelectDialogInput::RenameFailed(torrent_id, err));
+            }
             AppInput::OpenPrefsWindow => {
                 self.prefs_dialog.emit(PreferencesWindowInput::Open);
             }
@@ -478,42 +1283,33 @@ impl SimpleComponent for App {
                 self.transmission.emit(TransmissionInput::UpdateSettings);
             }
             AppInput::RightClickTorrent(x, y) => {
-                let guarded_view = self.view.guard();
-                let torrent_box = guarded_view.widget();
-
-                let clicked_row = match torrent_box.row_at_y(y as i32) {
-                    Some(row) => row,
-                    None => return,
+                let Some(clicked) = torrent::torrent_at(&self.torrent_list, x, y) else {
+                    return;
                 };
 
-                let mut found = false;
-                for row in torrent_box.selected_rows() {
-                    if row.eq(&clicked_row) {
-                        found = true;
-                        break;
+                let clicked_position = (0..self.selection.n_items()).find(|position| {
+                    self.selection.item(*position).as_ref() == Some(clicked.upcast_ref())
+                });
+                if let Some(position) = clicked_position {
+                    if !self.selection.is_selected(position) {
+                        self.selection.select_item(position, true);
                     }
                 }
 
-                if !found {
-                    torrent_box.unselect_all();
-                    torrent_box.select_row(Some(&clicked_row));
-                }
-
-                let items = torrent_box.selected_rows();
-                let mut all_paused = true;
-                for item in &items {
-                    let torrent = guarded_view.get(item.index() as usize).unwrap();
-                    if torrent.state != torrent::TorrentState::Stopped {
-                        all_paused = false;
-                        break;
-                    }
-                }
+                let items = self.selected_torrents();
+                let all_paused = items
+                    .iter()
+                    .all(|torrent| torrent.state() == torrent::TorrentState::Stopped);
 
                 let menu = gtk::gio::Menu::new();
                 menu.append(
-                    Some(&fl!("action-remove")),
+                    Some(&fl!("action-remove-menu")),
                     Some(&RemoveSelectedAction::action_name()),
                 );
+                menu.append(
+                    Some(&fl!("action-remove-and-delete-menu")),
+                    Some(&RemoveAndDeleteSelectedAction::action_name()),
+                );
 
                 if all_paused {
                     menu.append(
@@ -527,16 +1323,67 @@ impl SimpleComponent for App {
                     );
                 }
 
-                if items.len() < 2 {
-                    if let Some(selected_torrent) = guarded_view.get(items[0].index() as usize) {
-                        if selected_torrent.files.file_count > 1 {
-                            menu.append(
-                                Some(&fl!("action-select-files")),
-                                Some(&FileSelectAction::action_name()),
-                            );
-                        }
+                menu.append(
+                    Some(&fl!("action-start-now")),
+                    Some(&StartNowAction::action_name()),
+                );
+
+                let queue_menu = gtk::gio::Menu::new();
+                queue_menu.append(
+                    Some(&fl!("action-queue-move-top")),
+                    Some(&QueueMoveTopAction::action_name()),
+                );
+                queue_menu.append(
+                    Some(&fl!("action-queue-move-up")),
+                    Some(&QueueMoveUpAction::action_name()),
+                );
+                queue_menu.append(
+                    Some(&fl!("action-queue-move-down")),
+                    Some(&QueueMoveDownAction::action_name()),
+                );
+                queue_menu.append(
+                    Some(&fl!("action-queue-move-bottom")),
+                    Some(&QueueMoveBottomAction::action_name()),
+                );
+                menu.append_submenu(Some(&fl!("queue-menu")), &queue_menu);
+
+                menu.append(
+                    Some(&fl!("action-verify")),
+                    Some(&VerifySelectedAction::action_name()),
+                );
+                menu.append(
+                    Some(&fl!("action-reannounce")),
+                    Some(&ReannounceSelectedAction::action_name()),
+                );
+                menu.append(
+                    Some(&fl!("action-set-location")),
+                    Some(&SetLocationAction::action_name()),
+                );
+                menu.append(
+                    Some(&fl!("action-edit-labels")),
+                    Some(&EditLabelsAction::action_name()),
+                );
+
+                if let [selected_torrent] = items.as_slice() {
+                    if selected_torrent.file_count() > 1 {
+                        menu.append(
+                            Some(&fl!("action-select-files")),
+                            Some(&FileSelectAction::action_name()),
+                        );
                     }
 
+                    menu.append(
+                        Some(&fl!("action-details")),
+                        Some(&DetailsAction::action_name()),
+                    );
+                    menu.append(
+                        Some(&fl!("action-edit-trackers")),
+                        Some(&EditTrackersAction::action_name()),
+                    );
+                    menu.append(
+                        Some(&fl!("action-rename")),
+                        Some(&RenameSelectedAction::action_name()),
+                    );
                     menu.append(
                         Some(&fl!("action-copy-magnet")),
                         Some(&CopySelectedMagnetAction::action_name()),
@@ -547,34 +1394,155 @@ impl SimpleComponent for App {
                     );
                 }
 
+                // The menu hangs off the box around the list, so point at the click from there
+                let (x, y) = self
+                    .context_popover
+                    .parent()
+                    .and_then(|parent| self.torrent_list.translate_coordinates(&parent, x, y))
+                    .unwrap_or((x, y));
                 let rect = gtk::gdk::Rectangle::new(x as i32, y as i32, 0, 0);
                 self.context_popover.set_pointing_to(Some(&rect));
                 self.context_popover.set_menu_model(Some(&menu));
                 self.context_popover.popup();
             }
-            AppInput::ApplyFilter(filter_type) => {
-                self.current_filter = match filter_type {
-                    0 => FilterType::All,
-                    1 => FilterType::Downloading,
-                    2 => FilterType::Seeding,
-                    3 => FilterType::Paused,
-                    _ => FilterType::All,
-                };
+            AppInput::ApplyFilter(filter_id) => {
+                let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+                if let Err(err) = settings.set_string("filter", &filter_id) {
+                    log::error!("Error saving filter: {}", err);
+                }
+
+                self.current_filter = TorrentFilter::from_id(&filter_id);
+                self.sidebar.emit(SidebarInput::Select(filter_id));
                 self.apply_filter();
             }
-            AppInput::UpdateSearch(search_term) => {
-                self.search_term = search_term;
+            AppInput::ApplyLabelFilter(label) => {
+                self.current_label = label;
                 self.apply_filter();
             }
-            AppInput::OpenTorrentLocation => {
-                let items = self.view.guard().widget().selected_rows();
-                if items.len() == 1 {
-                    if let Some(torrent) = self.view.guard().get(items[0].index() as usize) {
-                        let download_dir = torrent.download_dir.clone();
-                        let torrent_name = torrent.name.clone();
+            AppInput::EditLabelsSelected => {
+                let selected = self.selected_torrents();
+                let selected_labels: Vec<Vec<String>> =
+                    selected.iter().map(|torrent| torrent.labels()).collect();
+
+                // Offer every label in use, not just the ones on the selected torrents
+                let label_counts = self
+                    .label_counts
+                    .iter()
+                    .map(|(label, _)| {
+                        let count = selected_labels
+                            .iter()
+                            .filter(|labels| labels.contains(label))
+                            .count();
+                        (label.clone(), count)
+                    })
+                    .collect();
+
+                self.labels_dialog.emit(LabelsDialogInput::Open(
+                    selected.iter().map(|torrent| torrent.hash()).collect(),
+                    label_counts,
+                ));
+            }
+            AppInput::ChangeLabels(hashes, added, removed) => {
+                for hash in hashes {
+                    let Some(torrent) = self.torrents.get(&hash) else {
+                        continue;
+                    };
 
-                        utils::open_torrent_location(&download_dir, &torrent_name);
+                    let current = torrent.labels();
+                    let mut labels: Vec<String> = current
+                        .iter()
+                        .filter(|label| !removed.contains(label))
+                        .cloned()
+                        .collect();
+                    for label in &added {
+                        if !labels.contains(label) {
+                            labels.push(label.clone());
+                        }
                     }
+
+                    if labels != current {
+                        self.transmission
+                            .emit(TransmissionInput::SetLabels(hash, labels));
+                    }
+                }
+            }
+            AppInput::SaveView => {
+                self.view_dialog
+                    .emit(ViewDialogInput::Open(None, self.view_names()));
+            }
+            AppInput::RenameView(name) => {
+                self.view_dialog
+                    .emit(ViewDialogInput::Open(Some(name), self.view_names()));
+            }
+            AppInput::NameView(Some(old_name), name) => {
+                let Some(view) = self.views.iter_mut().find(|view| view.name == old_name) else {
+                    return;
+                };
+                view.name.clone_from(&name);
+                self.views_changed();
+
+                if self.current_filter == TorrentFilter::View(old_name) {
+                    self.show_filter(TorrentFilter::View(name));
+                }
+            }
+            AppInput::NameView(None, name) => {
+                // Saving while a view is shown narrows that view down further
+                let view = match self.current_view() {
+                    Some(current) => {
+                        current.narrow(name.clone(), &self.current_label, self.query.text())
+                    }
+                    None => SavedView {
+                        name: name.clone(),
+                        filter: self.current_filter.id(),
+                        label: self.current_label.clone(),
+                        query: self.query.text().trim().to_string(),
+                    },
+                };
+
+                self.views.push(view);
+                self.views_changed();
+                self.show_filter(TorrentFilter::View(name));
+            }
+            AppInput::MoveView(name, up) => {
+                let Some(index) = self.views.iter().position(|view| view.name == name) else {
+                    return;
+                };
+                let target = if up {
+                    index.checked_sub(1)
+                } else {
+                    Some(index + 1).filter(|target| *target < self.views.len())
+                };
+
+                if let Some(target) = target {
+                    self.views.swap(index, target);
+                    self.views_changed();
+                }
+            }
+            AppInput::DeleteView(name) => {
+                self.views.retain(|view| view.name != name);
+                self.views_changed();
+
+                if self.current_filter == TorrentFilter::View(name) {
+                    self.show_filter(TorrentFilter::All);
+                }
+            }
+            AppInput::SetSortKey(sort_by) => {
+                self.sort_key = SortKey::from_id(&sort_by);
+                self.save_sort_order();
+                self.apply_sort();
+            }
+            AppInput::SetSortAscending(ascending) => {
+                self.sort_ascending = ascending;
+                self.save_sort_order();
+                self.apply_sort();
+            }
+            AppInput::UpdateSearch(query) => {
+                self.query = query;
+                self.apply_filter();
+            }
+            AppInput::OpenTorrentLocation => {
+                if let [torrent] = self.selected_torrents().as_slice() {
+                    utils::open_torrent_location(&torrent.download_dir(), &torrent.name());
                 }
             }
             AppInput::None => {}
@@ -583,54 +1551,178 @@ impl SimpleComponent for App {
 
     fn shutdown(&mut self, widgets: &mut Self::Widgets, _output: relm4::Sender<Self::Output>) {
         widgets.save_window_size().unwrap();
+
+        // Don't leave a progress bar on the launcher once we're gone
+        LauncherEntry::default().send();
     }
 }
 
 impl App {
-    fn apply_filter(&mut self) {
-        let search_term_lower = self.search_term.to_lowercase();
-        let guarded = self.view.guard();
-        let listbox = guarded.widget();
-
-        let mut index = 0;
-        let mut child = listbox.first_child();
-
-        while let Some(current_child) = child {
-            if let Some(row) = current_child.downcast_ref::<gtk::ListBoxRow>() {
-                let should_show = if let Some(torrent) = guarded.get(index) {
-                    // First check search term
-                    let matches_search = if self.search_term.is_empty() {
-                        true
-                    } else {
-                        torrent.name.to_lowercase().contains(&search_term_lower)
-                    };
+    fn save_sort_order(&self) {
+        let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+        if let Err(err) = settings.set_string("sort-by", self.sort_key.id()) {
+            log::error!("Error saving sort order: {}", err);
+        }
+        if let Err(err) = settings.set_boolean("sort-ascending", self.sort_ascending) {
+            log::error!("Error saving sort direction: {}", err);
+        }
+    }
 
-                    // Then check filter type
-                    if !matches_search {
-                        false
-                    } else {
-                        match self.current_filter {
-                            FilterType::All => true,
-                            FilterType::Downloading => {
-                                torrent.state == torrent::TorrentState::Downloading
-                                    || torrent.state == torrent::TorrentState::DownloadWaiting
-                            }
-                            FilterType::Seeding => {
-                                torrent.state == torrent::TorrentState::Seeding
-                                    || torrent.state == torrent::TorrentState::SeedWaiting
-                            }
-                            FilterType::Paused => torrent.state == torrent::TorrentState::Stopped,
-                        }
-                    }
-                } else {
-                    true
-                };
+    fn selected_torrents(&self) -> Vec<TorrentObject> {
+        let selected = self.selection.selection();
+        (0..selected.size() as u32)
+            .filter_map(|index| self.selection.item(selected.nth(index)))
+            .filter_map(|item| item.downcast::<TorrentObject>().ok())
+            .collect()
+    }
 
-                row.set_visible(should_show);
-                index += 1;
-            }
-            child = current_child.next_sibling();
+    fn selected_hashes(&self) -> Vec<String> {
+        self.selected_torrents()
+            .iter()
+            .map(|torrent| torrent.hash())
+            .collect()
+    }
+
+    /// Torrents still downloading into `folder`, including queued ones
+    fn torrents_downloading_to<'a>(
+        &'a self,
+        folder: &'a str,
+    ) -> impl Iterator<Item = &'a TorrentObject> + 'a {
+        self.torrents.values().filter(move |torrent| {
+            !torrent.state().is_stopped()
+                && torrent.data().left_until_done > 0
+                && torrent.download_dir().trim_end_matches('/') == folder
+        })
+    }
+
+    /// Warn once when a folder with downloads in progress is running out of space, and again
+    /// if it fills up after space was freed. Asking for free space can block on network
+    /// folders, so it's done on another thread and only every so often.
+    fn check_disk_space(&mut self, sender: &ComponentSender<Self>) {
+        if self.checking_disk_space
+            || self
+                .disk_space_checked
+                .is_some_and(|checked| checked.elapsed() < notifications::DISK_SPACE_CHECK_INTERVAL)
+        {
+            return;
         }
+
+        let folders: HashSet<String> = self
+            .torrents
+            .values()
+            .map(|torrent| torrent.download_dir().trim_end_matches('/').to_string())
+            .collect();
+        let folders: Vec<(String, i64)> = folders
+            .into_iter()
+            .map(|folder| {
+                let remaining = self
+                    .torrents_downloading_to(&folder)
+                    .map(|torrent| torrent.data().left_until_done)
+                    .sum();
+                (folder, remaining)
+            })
+            .collect();
+
+        self.checking_disk_space = true;
+        self.disk_space_checked = Some(Instant::now());
+        let sender = sender.clone();
+        relm4::spawn_blocking(move || {
+            let folders = folders
+                .into_iter()
+                .filter_map(|(folder, remaining)| {
+                    let free = notifications::free_space(&folder)?;
+                    Some((folder, remaining, free))
+                })
+                .collect();
+            sender.input(AppInput::DiskSpaceChecked(folders));
+        });
+    }
+
+    fn view_names(&self) -> Vec<String> {
+        self.views.iter().map(|view| view.name.clone()).collect()
+    }
+
+    /// The saved view being shown, if any
+    fn current_view(&self) -> Option<&SavedView> {
+        match &self.current_filter {
+            TorrentFilter::View(name) => self.views.iter().find(|view| view.name == *name),
+            _ => None,
+        }
+    }
+
+    fn views_changed(&self) {
+        views::save_views(&self.views);
+        self.sidebar
+            .emit(SidebarInput::ViewsChanged(self.views.clone()));
+        self.apply_filter();
+    }
+
+    /// Switch the list to another filter through its action, so the sidebar and the saved
+    /// choice follow along
+    fn show_filter(&self, filter: TorrentFilter) {
+        if let Err(err) = self.torrent_list.activate_action(
+            &FilterAction::action_name(),
+            Some(&filter.id().to_variant()),
+        ) {
+            log::error!("Error changing filter: {}", err);
+        }
+    }
+
+    /// Filter the list again after the search, filter or label changed. Only the filter's
+    /// function is replaced, which makes the model look at every torrent again.
+    fn apply_filter(&self) {
+        let now = gtk::glib::DateTime::now_utc()
+            .map(|now| now.to_unix())
+            .unwrap_or_default();
+        let matcher = ViewMatcher::new(
+            self.current_filter.clone(),
+            self.current_label.clone(),
+            self.query.clone(),
+        );
+        let view_matcher = self.current_view().map(SavedView::matcher);
+        let uses_age = matcher.uses_age()
+            || view_matcher
+                .as_ref()
+                .is_some_and(|view_matcher| view_matcher.uses_age());
+        self.age_filter_applied.set(uses_age.then_some(now));
+
+        self.filter.set_filter_func(move |item| {
+            let torrent = item
+                .downcast_ref::<TorrentObject>()
+                .expect("Needs to be TorrentObject");
+
+            matcher.matches(torrent, now)
+                && view_matcher
+                    .as_ref()
+                    .map_or(true, |view_matcher| view_matcher.matches(torrent, now))
+        });
+    }
+
+    /// Sort the list again, keeping torrents that compare equal in the order they're shown in
+    fn apply_sort(&self) {
+        let previous_order: HashMap<String, usize> = (0..self.sort_model.n_items())
+            .filter_map(|position| {
+                self.sort_model
+                    .item(position)
+                    .and_downcast::<TorrentObject>()
+            })
+            .enumerate()
+            .map(|(index, torrent)| (torrent.hash(), index))
+            .collect();
+        let sort_key = self.sort_key;
+        let ascending = self.sort_ascending;
+
+        self.sorter.set_sort_func(move |a, b| {
+            let a = a
+                .downcast_ref::<TorrentObject>()
+                .expect("Needs to be TorrentObject");
+            let b = b
+                .downcast_ref::<TorrentObject>()
+                .expect("Needs to be TorrentObject");
+
+            sort::compare_torrents(&a.data(), &b.data(), sort_key, ascending, &previous_order)
+                .into()
+        });
     }
 }
 
@@ -677,20 +1769,121 @@ fn torrent_file_filters() -> Vec<FileFilter> {
 }
 
 relm4::new_action_group!(WindowActionGroup, "win");
+relm4::new_action_group!(AppActionGroup, "app");
+relm4::new_stateful_action!(
+    OpenTorrentDataAction,
+    AppActionGroup,
+    "open-torrent-data",
+    String,
+    ()
+);
+relm4::new_stateful_action!(
+    ShowTorrentInFolderAction,
+    AppActionGroup,
+    "show-torrent-in-folder",
+    String,
+    ()
+);
+relm4::new_stateful_action!(
+    PauseTorrentAction,
+    AppActionGroup,
+    "pause-torrent",
+    String,
+    ()
+);
+relm4::new_stateful_action!(OpenFolderAction, AppActionGroup, "open-folder", String, ());
+relm4::new_stateful_action!(
+    PauseFolderAction,
+    AppActionGroup,
+    "pause-folder",
+    String,
+    ()
+);
+// On the application rather than the window, so the notification shown while the window
+// is hidden can quit too
+relm4::new_stateless_action!(QuitAction, AppActionGroup, "quit");
 relm4::new_stateless_action!(PreferencesAction, WindowActionGroup, "preferences");
-relm4::new_stateful_action!(FilterAction, WindowActionGroup, "filter", u8, u8);
+relm4::new_stateful_action!(FilterAction, WindowActionGroup, "filter", String, String);
+relm4::new_stateful_action!(
+    LabelFilterAction,
+    WindowActionGroup,
+    "label-filter",
+    String,
+    String
+);
+relm4::new_stateless_action!(SaveViewAction, WindowActionGroup, "save-view");
+relm4::new_stateful_action!(
+    RenameViewAction,
+    WindowActionGroup,
+    "rename-view",
+    String,
+    ()
+);
+relm4::new_stateful_action!(
+    MoveViewUpAction,
+    WindowActionGroup,
+    "move-view-up",
+    String,
+    ()
+);
+relm4::new_stateful_action!(
+    MoveViewDownAction,
+    WindowActionGroup,
+    "move-view-down",
+    String,
+    ()
+);
+relm4::new_stateful_action!(
+    DeleteViewAction,
+    WindowActionGroup,
+    "delete-view",
+    String,
+    ()
+);
+relm4::new_stateful_action!(SortByAction, WindowActionGroup, "sort-by", String, String);
+relm4::new_stateful_action!(
+    SortAscendingAction,
+    WindowActionGroup,
+    "sort-ascending",
+    (),
+    bool
+);
 relm4::new_stateless_action!(PauseSelectedAction, WindowActionGroup, "pause-selected");
 relm4::new_stateless_action!(ResumeSelectedAction, WindowActionGroup, "resume-selected");
+relm4::new_stateless_action!(VerifySelectedAction, WindowActionGroup, "verify-selected");
+relm4::new_stateless_action!(StartNowAction, WindowActionGroup, "start-now");
+relm4::new_stateless_action!(QueueMoveTopAction, WindowActionGroup, "queue-move-top");
+relm4::new_stateless_action!(QueueMoveUpAction, WindowActionGroup, "queue-move-up");
+relm4::new_stateless_action!(QueueMoveDownAction, WindowActionGroup, "queue-move-down");
+relm4::new_stateless_action!(
+    QueueMoveBottomAction,
+    WindowActionGroup,
+    "queue-move-bottom"
+);
 relm4::new_stateless_action!(RemoveSelectedAction, WindowActionGroup, "remove-selected");
+relm4::new_stateless_action!(
+    RemoveAndDeleteSelectedAction,
+    WindowActionGroup,
+    "remove-and-delete-selected"
+);
 relm4::new_stateless_action!(CopySelectedMagnetAction, WindowActionGroup, "copy-magnet");
 relm4::new_stateless_action!(FileSelectAction, WindowActionGroup, "file-select");
+relm4::new_stateless_action!(RenameSelectedAction, WindowActionGroup, "rename-selected");
+relm4::new_stateless_action!(DetailsAction, WindowActionGroup, "details");
+relm4::new_stateless_action!(EditTrackersAction, WindowActionGroup, "edit-trackers");
+relm4::new_stateless_action!(
+    ReannounceSelectedAction,
+    WindowActionGroup,
+    "reannounce-selected"
+);
+relm4::new_stateless_action!(SetLocationAction, WindowActionGroup, "set-location");
+relm4::new_stateless_action!(EditLabelsAction, WindowActionGroup, "edit-labels");
 relm4::new_stateless_action!(
     ShowInFileManagerAction,
     WindowActionGroup,
     "show-in-filemanager"
 );
 relm4::new_stateless_action!(OpenAction, WindowActionGroup, "open");
-relm4::new_stateless_action!(QuitAction, WindowActionGroup, "quit");
 
 fn main() {
     let requested_languages = DesktopLanguageRequester::requested_languages();
@@ -709,5 +1902,8 @@ fn main() {
     let theme = gtk::IconTheme::for_display(&display);
     theme.add_resource_path("/com/github/davidmhewitt/torrential/icons");
 
-    app.run::<App>(());
+    background::handle_command_line();
+    let args: Vec<String> = std::env::args().collect();
+    let background = args.iter().any(|arg| arg == background::BACKGROUND_FLAG);
+    app.with_args(args).run::<App>(background);
 }
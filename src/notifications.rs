@@ -0,0 +1,259 @@
+use crate::fl;
+use crate::rpc::Torrent;
+use crate::torrent::{TorrentObject, TorrentState};
+use gtk::gio;
+use gtk::prelude::{ApplicationExt, SettingsExt, ToVariant};
+use relm4::actions::ActionName;
+use relm4::gtk;
+use std::time::Duration;
+
+/// Below this much free space a download folder is always reported as nearly full
+const LOW_DISK_SPACE: u64 = 1_000_000_000;
+
+/// How often the free space of download folders is checked
+pub const DISK_SPACE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Finished,
+    Error,
+    Added,
+    DiskSpace,
+}
+
+impl NotificationKind {
+    fn setting(&self) -> &'static str {
+        match self {
+            NotificationKind::Finished => "notify-finished",
+            NotificationKind::Error => "notify-errors",
+            NotificationKind::Added => "notify-added",
+            NotificationKind::DiskSpace => "notify-disk-space",
+        }
+    }
+
+    /// Whether this kind of notification is turned on in preferences
+    pub fn enabled(&self) -> bool {
+        let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+        settings.boolean(self.setting())
+    }
+}
+
+/// What happened to a torrent between two snapshots from the daemon that's worth telling the
+/// user about. Verifying can take a torrent from partly to fully downloaded, which isn't a
+/// download finishing.
+pub fn torrent_changes(previous: &Torrent, current: &Torrent) -> Vec<NotificationKind> {
+    let verifying = |torrent: &Torrent| {
+        matches!(
+            TorrentState::try_from(torrent.status),
+            Ok(TorrentState::CheckWaiting | TorrentState::Checking)
+        )
+    };
+
+    let mut changes = vec![];
+    if previous.percent_done < 1.0
+        && current.percent_done >= 1.0
+        && !verifying(previous)
+        && !verifying(current)
+    {
+        changes.push(NotificationKind::Finished);
+    }
+    if previous.error == 0 && current.error != 0 {
+        changes.push(NotificationKind::Error);
+    }
+
+    changes
+}
+
+/// Whether a download folder with `free` bytes left is too full for the `remaining` bytes
+/// its torrents still have to download
+pub fn disk_nearly_full(free: u64, remaining: u64) -> bool {
+    free < LOW_DISK_SPACE.max(remaining)
+}
+
+/// Free space in bytes on the disk holding `folder`
+pub fn free_space(folder: &str) -> Option<u64> {
+    gio::File::for_path(folder)
+        .query_filesystem_info(gio::FILE_ATTRIBUTE_FILESYSTEM_FREE, gio::Cancellable::NONE)
+        .ok()
+        .map(|info| info.attribute_uint64(gio::FILE_ATTRIBUTE_FILESYSTEM_FREE))
+}
+
+pub fn notify_torrent(kind: NotificationKind, torrent: &TorrentObject) {
+    if !kind.enabled() {
+        return;
+    }
+
+    let name = torrent.name();
+    let hash = torrent.hash().to_variant();
+    let notification = match kind {
+        NotificationKind::Finished => {
+            let notification = gio::Notification::new(&fl!("notification-finished-title"));
+            notification.set_body(Some(&name));
+            notification.add_button_with_target_value(
+                &fl!("action-open-data"),
+                &crate::OpenTorrentDataAction::action_name(),
+                Some(&hash),
+            );
+            notification.add_button_with_target_value(
+                &fl!("action-show-in-folder"),
+                &crate::ShowTorrentInFolderAction::action_name(),
+                Some(&hash),
+            );
+            notification
+        }
+        NotificationKind::Error => {
+            let notification = gio::Notification::new(&fl!("notification-error-title"));
+            notification.set_body(Some(&fl!(
+                "notification-error-body",
+                name = name.clone(),
+                error = torrent.error_string()
+            )));
+            notification.set_priority(gio::NotificationPriority::High);
+            notification.add_button_with_target_value(
+                &fl!("action-show-in-folder"),
+                &crate::ShowTorrentInFolderAction::action_name(),
+                Some(&hash),
+            );
+            notification.add_button_with_target_value(
+                &fl!("action-pause"),
+                &crate::PauseTorrentAction::action_name(),
+                Some(&hash),
+            );
+            notification
+        }
+        NotificationKind::Added => {
+            let notification = gio::Notification::new(&fl!("notification-added-title"));
+            notification.set_body(Some(&name));
+            notification.add_button_with_target_value(
+                &fl!("action-pause"),
+                &crate::PauseTorrentAction::action_name(),
+                Some(&hash),
+            );
+            notification
+        }
+        NotificationKind::DiskSpace => return,
+    };
+
+    let id = format!("{}-{}", kind.setting(), torrent.hash());
+    relm4::main_application().send_notification(Some(&id), &notification);
+}
+
+pub fn notify_disk_space(folder: &str, free: u64) {
+    if !NotificationKind::DiskSpace.enabled() {
+        return;
+    }
+
+    let notification = gio::Notification::new(&fl!("notification-disk-space-title"));
+    notification.set_body(Some(&fl!(
+        "notification-disk-space-body",
+        folder = folder,
+        free = gtk::glib::format_size(free).to_string()
+    )));
+    notification.set_priority(gio::NotificationPriority::High);
+    notification.add_button_with_target_value(
+        &fl!("action-show-in-folder"),
+        &crate::OpenFolderAction::action_name(),
+        Some(&folder.to_variant()),
+    );
+    notification.add_button_with_target_value(
+        &fl!("action-pause-folder"),
+        &crate::PauseFolderAction::action_name(),
+        Some(&folder.to_variant()),
+    );
+
+    let id = format!("{}-{}", NotificationKind::DiskSpace.setting(), folder);
+    relm4::main_application().send_notification(Some(&id), &notification);
+}
+
+const RUNNING_IN_BACKGROUND_ID: &str = "running-in-background";
+
+/// Tell the user we're still running after the window was hidden. With no window on screen
+/// this is the only way left to quit, so it's shown whatever the notification preferences.
+pub fn notify_running_in_background() {
+    let notification = gio::Notification::new(&fl!("notification-background-title"));
+    notification.set_body(Some(&fl!("notification-background-body")));
+    notification.set_priority(gio::NotificationPriority::Low);
+    notification.add_button(&fl!("action-quit"), &crate::QuitAction::action_name());
+
+    relm4::main_application().send_notification(Some(RUNNING_IN_BACKGROUND_ID), &notification);
+}
+
+pub fn withdraw_running_in_background() {
+    relm4::main_application().withdraw_notification(RUNNING_IN_BACKGROUND_ID);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(status: i32, percent_done: f32, error: i32) -> Torrent {
+        Torrent {
+            torrent: transmission_client::Torrent {
+                status,
+                percent_done,
+                error,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    const DOWNLOADING: i32 = TorrentState::Downloading as i32;
+    const SEEDING: i32 = TorrentState::Seeding as i32;
+    const CHECKING: i32 = TorrentState::Checking as i32;
+    const CHECK_WAITING: i32 = TorrentState::CheckWaiting as i32;
+
+    #[test]
+    fn finishing_a_download() {
+        assert_eq!(
+            torrent_changes(&torrent(DOWNLOADING, 0.99, 0), &torrent(SEEDING, 1.0, 0)),
+            vec![NotificationKind::Finished]
+        );
+        assert!(torrent_changes(&torrent(SEEDING, 1.0, 0), &torrent(SEEDING, 1.0, 0)).is_empty());
+        assert!(
+            torrent_changes(&torrent(DOWNLOADING, 0.5, 0), &torrent(DOWNLOADING, 0.6, 0))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn verifying_is_not_finishing() {
+        for (before, after) in [
+            (CHECKING, CHECKING),
+            (CHECKING, SEEDING),
+            (CHECK_WAITING, SEEDING),
+            (DOWNLOADING, CHECKING),
+        ] {
+            assert!(
+                torrent_changes(&torrent(before, 0.4, 0), &torrent(after, 1.0, 0)).is_empty(),
+                "{before} -> {after}"
+            );
+        }
+    }
+
+    #[test]
+    fn new_errors() {
+        assert_eq!(
+            torrent_changes(&torrent(DOWNLOADING, 0.5, 0), &torrent(DOWNLOADING, 0.5, 3)),
+            vec![NotificationKind::Error]
+        );
+        assert!(
+            torrent_changes(&torrent(DOWNLOADING, 0.5, 3), &torrent(DOWNLOADING, 0.5, 2))
+                .is_empty()
+        );
+        assert_eq!(
+            torrent_changes(&torrent(DOWNLOADING, 0.9, 0), &torrent(SEEDING, 1.0, 2)),
+            vec![NotificationKind::Finished, NotificationKind::Error]
+        );
+    }
+
+    #[test]
+    fn nearly_full_disks() {
+        // Less free than is left to download
+        assert!(disk_nearly_full(5_000_000_000, 6_000_000_000));
+        assert!(!disk_nearly_full(6_000_000_000, 6_000_000_000));
+        // Under the minimum, however little is left
+        assert!(disk_nearly_full(LOW_DISK_SPACE - 1, 1));
+        assert!(!disk_nearly_full(LOW_DISK_SPACE, 1));
+    }
+}
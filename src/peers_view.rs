@@ -0,0 +1,309 @@
+use crate::fl;
+use crate::geoip::GeoIpDatabase;
+use crate::rpc::Peer;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use relm4::gtk;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+/// Where a peer was found, taken from the peer's flag string
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PeerSource {
+    #[default]
+    Tracker,
+    Dht,
+    Pex,
+    Lpd,
+    Incoming,
+}
+
+impl PeerSource {
+    fn from_flags(flags: &str) -> Self {
+        if flags.contains('H') {
+            PeerSource::Dht
+        } else if flags.contains('X') {
+            PeerSource::Pex
+        } else if flags.contains('L') {
+            PeerSource::Lpd
+        } else if flags.contains('I') {
+            PeerSource::Incoming
+        } else {
+            PeerSource::Tracker
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            PeerSource::Tracker => fl!("peer-source-tracker"),
+            PeerSource::Dht => fl!("peer-source-dht"),
+            PeerSource::Pex => fl!("peer-source-pex"),
+            PeerSource::Lpd => fl!("peer-source-lpd"),
+            PeerSource::Incoming => fl!("peer-source-incoming"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PeerRow {
+    pub address: String,
+    pub port: i32,
+    pub country: String,
+    pub client: String,
+    pub progress: f64,
+    pub rate_down: i64,
+    pub rate_up: i64,
+    pub flags: String,
+    pub source: PeerSource,
+}
+
+impl PeerRow {
+    pub fn new(peer: &Peer, geoip: Option<&GeoIpDatabase>) -> Self {
+        let mut flags = vec![];
+        if peer.is_encrypted {
+            flags.push(fl!("peer-flag-encrypted"));
+        }
+        if peer.is_utp {
+            flags.push(fl!("peer-flag-utp"));
+        }
+        if peer.client_is_choked {
+            flags.push(fl!("peer-flag-choked"));
+        }
+        if peer.client_is_interested {
+            flags.push(fl!("peer-flag-interested"));
+        }
+
+        Self {
+            address: peer.address.clone(),
+            port: peer.port,
+            country: geoip
+                .and_then(|geoip| geoip.country(&peer.address))
+                .unwrap_or_default()
+                .to_string(),
+            client: peer.client_name.clone(),
+            progress: peer.progress,
+            rate_down: peer.rate_to_client,
+            rate_up: peer.rate_to_peer,
+            flags: flags.join(", "),
+            source: PeerSource::from_flags(&peer.flag_str),
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct PeerObject {
+        pub row: RefCell<PeerRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PeerObject {
+        const NAME: &'static str = "TorrentialPeerObject";
+        type Type = super::PeerObject;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for PeerObject {}
+}
+
+glib::wrapper! {
+    pub struct PeerObject(ObjectSubclass<imp::PeerObject>);
+}
+
+impl PeerObject {
+    pub fn new(row: PeerRow) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().row.replace(row);
+        obj
+    }
+
+    pub fn row(&self) -> PeerRow {
+        self.imp().row.borrow().clone()
+    }
+}
+
+fn peer_row(item: &glib::Object) -> PeerRow {
+    item.downcast_ref::<PeerObject>()
+        .expect("Needs to be PeerObject")
+        .row()
+}
+
+fn peer_sorter<F>(compare: F) -> gtk::CustomSorter
+where
+    F: Fn(&PeerRow, &PeerRow) -> Ordering + 'static,
+{
+    gtk::CustomSorter::new(move |a, b| compare(&peer_row(a), &peer_row(b)).into())
+}
+
+/// A sortable column showing a line of text for each peer
+fn text_column<T, C>(title: &str, text: T, compare: C) -> gtk::ColumnViewColumn
+where
+    T: Fn(&PeerRow) -> String + 'static,
+    C: Fn(&PeerRow, &PeerRow) -> Ordering + 'static,
+{
+    let factory = gtk::SignalListItemFactory::new();
+
+    factory.connect_setup(|_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<gtk::ListItem>()
+            .expect("Needs to be ListItem");
+
+        let label = gtk::Label::new(None);
+        label.set_halign(gtk::Align::Start);
+        label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+        list_item.set_child(Some(&label));
+    });
+
+    factory.connect_bind(move |_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<gtk::ListItem>()
+            .expect("Needs to be ListItem");
+
+        let label = list_item
+            .child()
+            .and_downcast::<gtk::Label>()
+            .expect("Needs to be Label");
+
+        if let Some(item) = list_item.item() {
+            label.set_text(&text(&peer_row(&item)));
+        }
+    });
+
+    let column = gtk::ColumnViewColumn::new(Some(title), Some(factory));
+    column.set_resizable(true);
+    column.set_sorter(Some(&peer_sorter(compare)));
+    column
+}
+
+fn progress_column() -> gtk::ColumnViewColumn {
+    let factory = gtk::SignalListItemFactory::new();
+
+    factory.connect_setup(|_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<gtk::ListItem>()
+            .expect("Needs to be ListItem");
+
+        let progress_bar = gtk::ProgressBar::new();
+        progress_bar.set_valign(gtk::Align::Center);
+        list_item.set_child(Some(&progress_bar));
+    });
+
+    factory.connect_bind(|_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<gtk::ListItem>()
+            .expect("Needs to be ListItem");
+
+        let progress_bar = list_item
+            .child()
+            .and_downcast::<gtk::ProgressBar>()
+            .expect("Needs to be ProgressBar");
+
+        if let Some(item) = list_item.item() {
+            progress_bar.set_fraction(peer_row(&item).progress);
+        }
+    });
+
+    let column = gtk::ColumnViewColumn::new(Some(&fl!("peer-column-progress")), Some(factory));
+    column.set_fixed_width(100);
+    column.set_sorter(Some(&peer_sorter(|a, b| a.progress.total_cmp(&b.progress))));
+    column
+}
+
+/// A sortable table of peers, and the store backing it
+pub struct PeersView {
+    pub column_view: gtk::ColumnView,
+    pub store: gio::ListStore,
+    country_column: gtk::ColumnViewColumn,
+}
+
+impl PeersView {
+    pub fn new() -> Self {
+        let store = gio::ListStore::new::<PeerObject>();
+        let column_view = gtk::ColumnView::new(None::<gtk::SelectionModel>);
+        column_view.set_show_column_separators(true);
+
+        let address_column = text_column(
+            &fl!("peer-column-address"),
+            |peer| peer.address.clone(),
+            |a, b| {
+                let a_ip = a.address.parse::<std::net::IpAddr>().ok();
+                let b_ip = b.address.parse::<std::net::IpAddr>().ok();
+                a_ip.cmp(&b_ip)
+            },
+        );
+        address_column.set_expand(true);
+
+        let country_column = text_column(
+            &fl!("peer-column-country"),
+            |peer| peer.country.clone(),
+            |a, b| a.country.cmp(&b.country),
+        );
+        country_column.set_visible(false);
+
+        column_view.append_column(&address_column);
+        column_view.append_column(&text_column(
+            &fl!("peer-column-port"),
+            |peer| peer.port.to_string(),
+            |a, b| a.port.cmp(&b.port),
+        ));
+        column_view.append_column(&country_column);
+        column_view.append_column(&text_column(
+            &fl!("peer-column-client"),
+            |peer| peer.client.clone(),
+            |a, b| a.client.to_lowercase().cmp(&b.client.to_lowercase()),
+        ));
+        column_view.append_column(&progress_column());
+        column_view.append_column(&text_column(
+            &fl!("peer-column-download"),
+            |peer| format!("{}/s", glib::format_size(peer.rate_down as u64)),
+            |a, b| a.rate_down.cmp(&b.rate_down),
+        ));
+        column_view.append_column(&text_column(
+            &fl!("peer-column-upload"),
+            |peer| format!("{}/s", glib::format_size(peer.rate_up as u64)),
+            |a, b| a.rate_up.cmp(&b.rate_up),
+        ));
+        column_view.append_column(&text_column(
+            &fl!("peer-column-flags"),
+            |peer| peer.flags.clone(),
+            |a, b| a.flags.cmp(&b.flags),
+        ));
+        column_view.append_column(&text_column(
+            &fl!("peer-column-source"),
+            |peer| peer.source.label(),
+            |a, b| a.source.cmp(&b.source),
+        ));
+
+        let sort_model = gtk::SortListModel::new(Some(store.clone()), column_view.sorter());
+        column_view.set_model(Some(&gtk::NoSelection::new(Some(sort_model))));
+
+        Self {
+            column_view,
+            store,
+            country_column,
+        }
+    }
+
+    pub fn set_peers(&self, peers: &[Peer], geoip: Option<&GeoIpDatabase>) {
+        self.country_column.set_visible(geoip.is_some());
+
+        let objects: Vec<PeerObject> = peers
+            .iter()
+            .map(|peer| PeerObject::new(PeerRow::new(peer, geoip)))
+            .collect();
+        self.store.splice(0, self.store.n_items(), &objects);
+    }
+
+    pub fn clear(&self) {
+        self.store.remove_all();
+    }
+}
+
+impl Default for PeersView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,210 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, glib, graphene};
+use relm4::gtk;
+use std::cell::RefCell;
+
+/// Decode the daemon's `pieces` field: a base64 bitfield with the first piece in the most
+/// significant bit of the first byte. Pieces missing from a short bitfield count as not done.
+pub fn decode_pieces(encoded: &str, piece_count: usize) -> Vec<bool> {
+    let bytes = glib::base64_decode(encoded.trim());
+
+    (0..piece_count)
+        .map(|piece| {
+            bytes
+                .get(piece / 8)
+                .is_some_and(|byte| byte & (0x80 >> (piece % 8)) != 0)
+        })
+        .collect()
+}
+
+/// The state of a run of pieces drawn as one cell, once there are more pieces than pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    done: f32,
+    unavailable: bool,
+}
+
+fn cells(pieces: &[bool], availability: &[i32], count: usize) -> Vec<Cell> {
+    if pieces.is_empty() || count == 0 {
+        return vec![];
+    }
+
+    let count = count.min(pieces.len());
+    (0..count)
+        .map(|cell| {
+            let start = cell * pieces.len() / count;
+            let end = ((cell + 1) * pieces.len() / count).max(start + 1);
+
+            let done = pieces[start..end].iter().filter(|done| **done).count();
+            let unavailable = (start..end)
+                .any(|piece| !pieces[piece] && availability.get(piece).is_some_and(|a| *a == 0));
+
+            Cell {
+                done: done as f32 / (end - start) as f32,
+                unavailable,
+            }
+        })
+        .collect()
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct PieceMap {
+        pub pieces: RefCell<Vec<bool>>,
+        pub availability: RefCell<Vec<i32>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PieceMap {
+        const NAME: &'static str = "TorrentialPieceMap";
+        type Type = super::PieceMap;
+        type ParentType = gtk::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("piecemap");
+        }
+    }
+
+    impl ObjectImpl for PieceMap {}
+
+    impl WidgetImpl for PieceMap {
+        fn measure(&self, orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            match orientation {
+                gtk::Orientation::Horizontal => (32, 200, -1, -1),
+                _ => (16, 24, -1, -1),
+            }
+        }
+
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let widget = self.obj();
+            let width = widget.width() as f32;
+            let height = widget.height() as f32;
+
+            let pieces = self.pieces.borrow();
+            let availability = self.availability.borrow();
+            let cells = cells(&pieces, &availability, width as usize);
+            if cells.is_empty() {
+                return;
+            }
+
+            let done_color = widget.color();
+            let missing_color = gdk::RGBA::new(
+                done_color.red(),
+                done_color.green(),
+                done_color.blue(),
+                0.15,
+            );
+            let unavailable_color = gdk::RGBA::new(0.78, 0.15, 0.16, 0.6);
+
+            snapshot.append_color(
+                &missing_color,
+                &graphene::Rect::new(0.0, 0.0, width, height),
+            );
+
+            let cell_width = width / cells.len() as f32;
+            for (index, cell) in cells.iter().enumerate() {
+                let bounds =
+                    graphene::Rect::new(index as f32 * cell_width, 0.0, cell_width, height);
+
+                if cell.done > 0.0 {
+                    let color = gdk::RGBA::new(
+                        done_color.red(),
+                        done_color.green(),
+                        done_color.blue(),
+                        cell.done,
+                    );
+                    snapshot.append_color(&color, &bounds);
+                } else if cell.unavailable {
+                    snapshot.append_color(&unavailable_color, &bounds);
+                }
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct PieceMap(ObjectSubclass<imp::PieceMap>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl PieceMap {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Update which pieces are done, and how many peers have each piece where the daemon
+    /// reports it. Only redraws if something changed.
+    pub fn set_pieces(&self, pieces: Vec<bool>, availability: Vec<i32>) {
+        let imp = self.imp();
+        if *imp.pieces.borrow() == pieces && *imp.availability.borrow() == availability {
+            return;
+        }
+
+        imp.pieces.replace(pieces);
+        imp.availability.replace(availability);
+        self.queue_draw();
+    }
+
+    pub fn clear(&self) {
+        self.set_pieces(vec![], vec![]);
+    }
+}
+
+impl Default for PieceMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_reads_most_significant_bit_first() {
+        // 0b1010_0000
+        assert_eq!(decode_pieces("oA==", 4), vec![true, false, true, false]);
+    }
+
+    #[test]
+    fn decode_spans_multiple_bytes() {
+        // 0xff, 0x80
+        let pieces = decode_pieces("/4A=", 10);
+        assert_eq!(pieces.len(), 10);
+        assert!(pieces[..9].iter().all(|done| *done));
+        assert!(!pieces[9]);
+    }
+
+    #[test]
+    fn decode_ignores_padding_bits_past_the_piece_count() {
+        // 0xff, but only three pieces
+        assert_eq!(decode_pieces("/w==", 3), vec![true, true, true]);
+    }
+
+    #[test]
+    fn decode_short_bitfield_counts_missing_pieces_as_not_done() {
+        assert_eq!(decode_pieces("", 3), vec![false, false, false]);
+        assert_eq!(decode_pieces("gA==", 9).iter().filter(|d| **d).count(), 1);
+    }
+
+    #[test]
+    fn cells_average_pieces_when_there_are_more_pieces_than_pixels() {
+        let pieces = vec![true, false, true, true];
+        let cells = cells(&pieces, &[], 2);
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].done, 0.5);
+        assert_eq!(cells[1].done, 1.0);
+    }
+
+    #[test]
+    fn cells_flag_missing_pieces_no_peer_has() {
+        let cells = cells(&[true, false, false], &[3, 0, 2], 3);
+        assert!(!cells[0].unavailable);
+        assert!(cells[1].unavailable);
+        assert!(!cells[2].unavailable);
+    }
+}
@@ -1,11 +1,12 @@
+use crate::background;
 use crate::fl;
 use granite::prelude::HeaderLabelExt;
 use gtk::prelude::{
-    BoxExt, ButtonExt, DialogExt, GridExt, GtkWindowExt, OrientableExt, SettingsExtManual,
-    WidgetExt,
+    BoxExt, ButtonExt, DialogExt, FileExt, GridExt, GtkWindowExt, OrientableExt, SettingsExt,
+    SettingsExtManual, WidgetExt,
 };
 use relm4::gtk;
-use relm4::gtk::gio::SettingsBindFlags;
+use relm4::gtk::gio::{self, SettingsBindFlags};
 use relm4::ComponentSender;
 use relm4::SimpleComponent;
 
@@ -148,6 +149,70 @@ impl SimpleComponent for PreferencesWindowModel {
                             set_halign: gtk::Align::Start,
                             set_hexpand: true,
                         },
+
+                        attach[0, 8, 1, 1] = &gtk::Label {
+                            set_label: &fl!("label-start-on-login"),
+                            set_halign: gtk::Align::End,
+                            set_margin_start: 12,
+                        },
+
+                        #[name = "start_on_login_switch"]
+                        attach[1, 8, 1, 1] = &gtk::Switch {
+                            set_halign: gtk::Align::Start,
+                            set_hexpand: true,
+                        },
+
+                        attach[0, 9, 1, 1] = &granite::HeaderLabel {
+                            set_label: &fl!("header-notifications"),
+                        },
+
+                        attach[0, 10, 1, 1] = &gtk::Label {
+                            set_label: &fl!("label-notify-finished"),
+                            set_halign: gtk::Align::End,
+                            set_margin_start: 12,
+                        },
+
+                        #[name = "notify_finished_switch"]
+                        attach[1, 10, 1, 1] = &gtk::Switch {
+                            set_halign: gtk::Align::Start,
+                            set_hexpand: true,
+                        },
+
+                        attach[0, 11, 1, 1] = &gtk::Label {
+                            set_label: &fl!("label-notify-errors"),
+                            set_halign: gtk::Align::End,
+                            set_margin_start: 12,
+                        },
+
+                        #[name = "notify_errors_switch"]
+                        attach[1, 11, 1, 1] = &gtk::Switch {
+                            set_halign: gtk::Align::Start,
+                            set_hexpand: true,
+                        },
+
+                        attach[0, 12, 1, 1] = &gtk::Label {
+                            set_label: &fl!("label-notify-added"),
+                            set_halign: gtk::Align::End,
+                            set_margin_start: 12,
+                        },
+
+                        #[name = "notify_added_switch"]
+                        attach[1, 12, 1, 1] = &gtk::Switch {
+                            set_halign: gtk::Align::Start,
+                            set_hexpand: true,
+                        },
+
+                        attach[0, 13, 1, 1] = &gtk::Label {
+                            set_label: &fl!("label-notify-disk-space"),
+                            set_halign: gtk::Align::End,
+                            set_margin_start: 12,
+                        },
+
+                        #[name = "notify_disk_space_switch"]
+                        attach[1, 13, 1, 1] = &gtk::Switch {
+                            set_halign: gtk::Align::Start,
+                            set_hexpand: true,
+                        },
                     } -> {
                         set_title: &fl!("general-preferences-title")
                     },
@@ -201,6 +266,46 @@ impl SimpleComponent for PreferencesWindowModel {
                             set_hexpand: true,
                         },
 
+                        attach[0, 4, 1, 1] = &granite::HeaderLabel {
+                            set_label: &fl!("header-peers"),
+                        },
+
+                        attach[0, 5, 1, 1] = &gtk::Label {
+                            set_label: &fl!("label-geoip-database"),
+                            set_halign: gtk::Align::End,
+                            set_margin_start: 12,
+                        },
+
+                        attach[1, 5, 1, 1] = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            add_css_class: "linked",
+                            set_hexpand: true,
+                            set_tooltip_text: Some(&fl!("tooltip-geoip-database")),
+
+                            #[name = "geoip_button"]
+                            gtk::Button {
+                                set_hexpand: true,
+                                #[wrap(Some)]
+                                set_child = &gtk::Box {
+                                    set_orientation: gtk::Orientation::Horizontal,
+                                    set_spacing: 3,
+
+                                    gtk::Image {
+                                        set_icon_name: Some("document-open-symbolic"),
+                                    },
+
+                                    #[name = "geoip_label"]
+                                    gtk::Label {
+                                        set_ellipsize: gtk::pango::EllipsizeMode::Start,
+                                    },
+                                },
+                            },
+
+                            #[name = "geoip_clear_button"]
+                            gtk::Button {
+                                set_icon_name: "edit-clear-symbolic",
+                            },
+                        },
                     } -> {
                         set_title: &fl!("advanced-preferences-title")
                     },
@@ -213,7 +318,7 @@ impl SimpleComponent for PreferencesWindowModel {
 
     fn init(
         init: Self::Init,
-        _parent: Self::Root,
+        root: Self::Root,
         sender: relm4::ComponentSender<Self>,
     ) -> relm4::ComponentParts<Self> {
         let model = Self { hidden: init };
@@ -248,6 +353,28 @@ impl SimpleComponent for PreferencesWindowModel {
             .flags(SettingsBindFlags::DEFAULT)
             .build();
 
+        settings
+            .bind("start-on-login", &widgets.start_on_login_switch, "active")
+            .flags(SettingsBindFlags::DEFAULT)
+            .build();
+
+        // Connected after binding so only changes made here register with the system
+        widgets
+            .start_on_login_switch
+            .connect_active_notify(|_| background::update_autostart());
+
+        for (key, switch) in [
+            ("notify-finished", &widgets.notify_finished_switch),
+            ("notify-errors", &widgets.notify_errors_switch),
+            ("notify-added", &widgets.notify_added_switch),
+            ("notify-disk-space", &widgets.notify_disk_space_switch),
+        ] {
+            settings
+                .bind(key, switch, "active")
+                .flags(SettingsBindFlags::DEFAULT)
+                .build();
+        }
+
         settings
             .bind("randomize-port", &widgets.randomise_port_switch, "active")
             .flags(SettingsBindFlags::DEFAULT)
@@ -258,6 +385,38 @@ impl SimpleComponent for PreferencesWindowModel {
             .flags(SettingsBindFlags::DEFAULT)
             .build();
 
+        settings
+            .bind("geoip-database", &widgets.geoip_label, "label")
+            .flags(SettingsBindFlags::GET)
+            .build();
+
+        widgets.geoip_button.connect_clicked({
+            let settings = settings.clone();
+            let root = root.clone();
+            move |_| {
+                let settings = settings.clone();
+                gtk::FileDialog::builder().modal(true).build().open(
+                    Some(&root),
+                    gio::Cancellable::NONE,
+                    move |result| {
+                        if let Some(path) = result.ok().and_then(|file| file.path()) {
+                            if let Err(err) =
+                                settings.set_string("geoip-database", &path.to_string_lossy())
+                            {
+                                log::error!("Error saving GeoIP database path: {}", err);
+                            }
+                        }
+                    },
+                );
+            }
+        });
+
+        widgets.geoip_clear_button.connect_clicked(move |_| {
+            if let Err(err) = settings.set_string("geoip-database", "") {
+                log::error!("Error clearing GeoIP database path: {}", err);
+            }
+        });
+
         relm4::ComponentParts { model, widgets }
     }
 
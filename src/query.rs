@@ -0,0 +1,589 @@
+use crate::fl;
+use crate::sidebar::TorrentFilter;
+use crate::torrent::TorrentObject;
+
+/// The search syntax typed into the header. Words match the torrent name, and a word can be
+/// narrowed to a field with `field:value`:
+///
+/// - `tracker:`, `label:` and `file:` match tracker domains, labels and file names
+/// - `state:` takes a sidebar state such as `downloading`, `paused` or `errors`
+/// - `size:`, `ratio:` and `added:` take an optional comparison, e.g. `size:>4G`, `ratio:<1`
+///   or `added:<7d` for torrents added in the last week
+///
+/// Double quotes keep spaces in a phrase or value, with `\"` and `\\` for a quote or backslash
+/// inside them, and a leading `-` negates a term. A torrent has to match every term to be shown.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    text: String,
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+    pub negated: bool,
+    pub condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Name(String), // lowercase
+    Tracker(String),
+    Label(String),
+    File(String),
+    State(TorrentFilter),
+    Size(Comparison, i64),  // bytes
+    Ratio(Comparison, f64), // uploaded / downloaded
+    Added(Comparison, i64), // seconds since the torrent was added
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum QueryError {
+    UnterminatedQuote,
+    UnknownField(String),
+    MissingValue(String),         // field
+    InvalidValue(String, String), // field, value
+}
+
+impl QueryError {
+    pub fn message(&self) -> String {
+        match self {
+            QueryError::UnterminatedQuote => fl!("query-error-unterminated-quote"),
+            QueryError::UnknownField(field) => {
+                fl!("query-error-unknown-field", field = field.clone())
+            }
+            QueryError::MissingValue(field) => {
+                fl!("query-error-missing-value", field = field.clone())
+            }
+            QueryError::InvalidValue(field, value) => fl!(
+                "query-error-invalid-value",
+                field = field.clone(),
+                value = value.clone()
+            ),
+        }
+    }
+}
+
+impl Comparison {
+    /// Split a leading comparison operator off `value`, defaulting to equality
+    fn split(value: &str) -> (Comparison, &str) {
+        let operators = [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+        ];
+
+        operators
+            .into_iter()
+            .find_map(|(operator, comparison)| {
+                value
+                    .strip_prefix(operator)
+                    .map(|rest| (comparison, rest.trim_start()))
+            })
+            .unwrap_or((Comparison::Equal, value))
+    }
+
+    fn compare<T: PartialOrd>(self, value: T, target: T) -> bool {
+        match self {
+            Comparison::Less => value < target,
+            Comparison::LessOrEqual => value <= target,
+            Comparison::Equal => value == target,
+            Comparison::GreaterOrEqual => value >= target,
+            Comparison::Greater => value > target,
+        }
+    }
+}
+
+/// One whitespace separated piece of the query, before its value is interpreted
+struct Token {
+    negated: bool,
+    field: Option<String>,
+    value: String,
+    quoted: bool,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, QueryError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = vec![];
+    let mut index = 0;
+
+    loop {
+        while chars.get(index).is_some_and(|c| c.is_whitespace()) {
+            index += 1;
+        }
+        if index >= chars.len() {
+            break;
+        }
+
+        // A lone "-" is just a word, not a negation of nothing
+        let negated = chars[index] == '-'
+            && chars
+                .get(index + 1)
+                .is_some_and(|next| !next.is_whitespace());
+        if negated {
+            index += 1;
+        }
+
+        let mut token = Token {
+            negated,
+            field: None,
+            value: String::new(),
+            quoted: false,
+        };
+        while let Some(&c) = chars.get(index) {
+            if c.is_whitespace() {
+                break;
+            }
+            index += 1;
+
+            match c {
+                '"' => {
+                    token.quoted = true;
+                    loop {
+                        match chars.get(index) {
+                            Some('"') => break,
+                            Some('\\') if matches!(chars.get(index + 1), Some('"' | '\\')) => {
+                                index += 1;
+                                token.value.push(chars[index]);
+                            }
+                            Some(&c) => token.value.push(c),
+                            None => return Err(QueryError::UnterminatedQuote),
+                        }
+                        index += 1;
+                    }
+                    index += 1;
+                }
+                ':' if token.field.is_none() && !token.quoted && !token.value.is_empty() => {
+                    token.field = Some(std::mem::take(&mut token.value).to_lowercase());
+                }
+                c => token.value.push(c),
+            }
+        }
+
+        // An empty phrase doesn't narrow anything down
+        if token.field.is_some() || !token.value.is_empty() {
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Quote a value so that it's read back as a single phrase, whatever it contains
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn parse_state(value: &str) -> Option<TorrentFilter> {
+    match value {
+        "error" => Some(TorrentFilter::Errors),
+        "verifying" => Some(TorrentFilter::Checking),
+        "stopped" => Some(TorrentFilter::Paused),
+        _ => TorrentFilter::STATES
+            .into_iter()
+            .find(|filter| filter.id() == value),
+    }
+}
+
+/// Split a number from the unit after it, e.g. `4.5GB` into `4.5` and `gb`
+fn split_number(value: &str) -> Option<(f64, String)> {
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let number = value[..unit_start].parse::<f64>().ok()?;
+    if !number.is_finite() || number < 0.0 {
+        return None;
+    }
+
+    Some((number, value[unit_start..].trim().to_lowercase()))
+}
+
+/// A size in bytes. Units are decimal like the sizes shown in the list, with the binary
+/// `KiB` style units also accepted.
+fn parse_size(value: &str) -> Option<i64> {
+    let (number, unit) = split_number(value)?;
+    let multiplier: f64 = match unit.as_str() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "t" | "tb" => 1e12,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+
+    Some((number * multiplier).round() as i64)
+}
+
+/// An age in seconds, in hours, days (the default), weeks or years
+fn parse_age(value: &str) -> Option<i64> {
+    let (number, unit) = split_number(value)?;
+    let multiplier: f64 = match unit.as_str() {
+        "h" => 60.0 * 60.0,
+        "" | "d" => 24.0 * 60.0 * 60.0,
+        "w" => 7.0 * 24.0 * 60.0 * 60.0,
+        "y" => 365.0 * 24.0 * 60.0 * 60.0,
+        _ => return None,
+    };
+
+    Some((number * multiplier).round() as i64)
+}
+
+fn parse_term(token: Token) -> Result<Term, QueryError> {
+    let Some(field) = token.field else {
+        return Ok(Term {
+            negated: token.negated,
+            condition: Condition::Name(token.value.to_lowercase()),
+        });
+    };
+
+    if token.value.is_empty() {
+        return Err(QueryError::MissingValue(field));
+    }
+
+    let invalid = || QueryError::InvalidValue(field.clone(), token.value.clone());
+    let lowercase = token.value.to_lowercase();
+    let condition = match field.as_str() {
+        "tracker" => Condition::Tracker(lowercase),
+        "label" => Condition::Label(lowercase),
+        "file" => Condition::File(lowercase),
+        "state" => Condition::State(parse_state(&lowercase).ok_or_else(invalid)?),
+        "size" => {
+            let (comparison, size) = Comparison::split(&token.value);
+            Condition::Size(comparison, parse_size(size).ok_or_else(invalid)?)
+        }
+        "ratio" => {
+            let (comparison, ratio) = Comparison::split(&token.value);
+            let ratio = ratio
+                .parse::<f64>()
+                .ok()
+                .filter(|ratio| ratio.is_finite())
+                .ok_or_else(invalid)?;
+            Condition::Ratio(comparison, ratio)
+        }
+        "added" => {
+            let (comparison, age) = Comparison::split(&token.value);
+            Condition::Added(comparison, parse_age(age).ok_or_else(invalid)?)
+        }
+        _ => return Err(QueryError::UnknownField(field.clone())),
+    };
+
+    Ok(Term {
+        negated: token.negated,
+        condition,
+    })
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Query, QueryError> {
+        let terms = tokenize(text)?
+            .into_iter()
+            .map(parse_term)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Query {
+            text: text.to_string(),
+            terms,
+        })
+    }
+
+    /// What was typed to make this query
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn terms(&self) -> &[Term] {
+        &self.terms
+    }
+
+    /// Whether what the query matches changes over time, through an `added:` term
+    pub fn uses_age(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|term| matches!(term.condition, Condition::Added(..)))
+    }
+
+    /// Whether the torrent matches every term. `now` is the current Unix time, for `added:`.
+    pub fn matches(&self, torrent: &TorrentObject, now: i64) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.condition.matches(torrent, now) != term.negated)
+    }
+}
+
+impl Condition {
+    fn matches(&self, torrent: &TorrentObject, now: i64) -> bool {
+        match self {
+            Condition::Name(text) => torrent.name().to_lowercase().contains(text),
+            Condition::Tracker(text) => torrent
+                .tracker_domains()
+                .iter()
+                .any(|domain| domain.contains(text)),
+            Condition::Label(label) => torrent
+                .labels()
+                .iter()
+                .any(|existing| existing.to_lowercase() == *label),
+            Condition::File(text) => torrent.has_file_matching(text),
+            Condition::State(filter) => filter.matches(torrent),
+            Condition::Size(comparison, size) => {
+                comparison.compare(torrent.data().total_size, *size)
+            }
+            Condition::Ratio(comparison, ratio) => {
+                // The daemon reports a negative ratio before there is one, which no
+                // comparison should match
+                let upload_ratio = torrent.data().upload_ratio;
+                upload_ratio >= 0.0 && comparison.compare(upload_ratio as f64, *ratio)
+            }
+            Condition::Added(comparison, age) => {
+                comparison.compare(now - torrent.data().added_date as i64, *age)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i18n_embed::LanguageLoader;
+
+    fn conditions(text: &str) -> Vec<(bool, Condition)> {
+        Query::parse(text)
+            .unwrap()
+            .terms()
+            .iter()
+            .map(|term| (term.negated, term.condition.clone()))
+            .collect()
+    }
+
+    fn with_ratio(upload_ratio: f32) -> TorrentObject {
+        crate::STATIC_LANGUAGE_LOADER
+            .load_fallback_language(&crate::Localizations)
+            .unwrap();
+
+        TorrentObject::new(crate::rpc::Torrent {
+            torrent: transmission_client::Torrent {
+                upload_ratio,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert!(Query::parse("").unwrap().terms().is_empty());
+        assert!(Query::parse("   ").unwrap().terms().is_empty());
+    }
+
+    #[test]
+    fn words_match_the_name() {
+        assert_eq!(
+            conditions("Ubuntu  ISO"),
+            vec![
+                (false, Condition::Name("ubuntu".to_string())),
+                (false, Condition::Name("iso".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_phrases_keep_spaces() {
+        assert_eq!(
+            conditions("\"Big Buck\" bunny"),
+            vec![
+                (false, Condition::Name("big buck".to_string())),
+                (false, Condition::Name("bunny".to_string())),
+            ]
+        );
+        assert_eq!(
+            conditions("label:\"tv shows\""),
+            vec![(false, Condition::Label("tv shows".to_string()))]
+        );
+    }
+
+    #[test]
+    fn escapes_inside_quotes() {
+        assert_eq!(
+            conditions(r#"label:"say \"hi\"" "C:\\films\new""#),
+            vec![
+                (false, Condition::Label("say \"hi\"".to_string())),
+                (false, Condition::Name("c:\\films\\new".to_string())),
+            ]
+        );
+        assert_eq!(
+            Query::parse(r#""trailing\""#),
+            Err(QueryError::UnterminatedQuote)
+        );
+    }
+
+    #[test]
+    fn quoted_values_round_trip() {
+        for label in ["tv shows", "a \"b\" c", "\"", "back\\slash\\", "\\\""] {
+            assert_eq!(
+                conditions(&format!("label:{}", quote(label))),
+                vec![(false, Condition::Label(label.to_lowercase()))]
+            );
+        }
+    }
+
+    #[test]
+    fn colons_inside_quotes_are_not_fields() {
+        assert_eq!(
+            conditions("\"re:zero\""),
+            vec![(false, Condition::Name("re:zero".to_string()))]
+        );
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!(
+            conditions("-sample -label:old"),
+            vec![
+                (true, Condition::Name("sample".to_string())),
+                (true, Condition::Label("old".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn lone_dash_is_a_word() {
+        assert_eq!(
+            conditions("a - b"),
+            vec![
+                (false, Condition::Name("a".to_string())),
+                (false, Condition::Name("-".to_string())),
+                (false, Condition::Name("b".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn text_fields() {
+        assert_eq!(
+            conditions("Tracker:OpenTrackr file:.MKV"),
+            vec![
+                (false, Condition::Tracker("opentrackr".to_string())),
+                (false, Condition::File(".mkv".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn states() {
+        assert_eq!(
+            conditions("state:seeding -state:error"),
+            vec![
+                (false, Condition::State(TorrentFilter::Seeding)),
+                (true, Condition::State(TorrentFilter::Errors)),
+            ]
+        );
+        assert_eq!(
+            Query::parse("state:sleeping"),
+            Err(QueryError::InvalidValue(
+                "state".to_string(),
+                "sleeping".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(
+            conditions("size:>4G size:<=1.5MiB size:100"),
+            vec![
+                (false, Condition::Size(Comparison::Greater, 4_000_000_000)),
+                (false, Condition::Size(Comparison::LessOrEqual, 1_572_864)),
+                (false, Condition::Size(Comparison::Equal, 100)),
+            ]
+        );
+        assert!(Query::parse("size:>4Q").is_err());
+        assert!(Query::parse("size:>").is_err());
+    }
+
+    #[test]
+    fn ratios() {
+        assert_eq!(
+            conditions("ratio:<1 ratio:>=2.5"),
+            vec![
+                (false, Condition::Ratio(Comparison::Less, 1.0)),
+                (false, Condition::Ratio(Comparison::GreaterOrEqual, 2.5)),
+            ]
+        );
+        assert!(Query::parse("ratio:lots").is_err());
+    }
+
+    #[test]
+    fn ratios_only_match_once_there_is_one() {
+        let less = Query::parse("ratio:<1").unwrap();
+        let at_least = Query::parse("ratio:>=0").unwrap();
+
+        assert!(less.matches(&with_ratio(0.5), 0));
+        assert!(at_least.matches(&with_ratio(0.0), 0));
+        assert!(!less.matches(&with_ratio(-1.0), 0));
+        assert!(!at_least.matches(&with_ratio(-1.0), 0));
+        assert!(!less.matches(&with_ratio(-2.0), 0));
+    }
+
+    #[test]
+    fn added_ages() {
+        assert_eq!(
+            conditions("added:<7d added:>12h added:2w"),
+            vec![
+                (false, Condition::Added(Comparison::Less, 7 * 24 * 60 * 60)),
+                (false, Condition::Added(Comparison::Greater, 12 * 60 * 60)),
+                (
+                    false,
+                    Condition::Added(Comparison::Equal, 14 * 24 * 60 * 60)
+                ),
+            ]
+        );
+        assert!(Query::parse("added:<7fortnights").is_err());
+    }
+
+    #[test]
+    fn only_added_terms_use_age() {
+        assert!(Query::parse("added:<7d").unwrap().uses_age());
+        assert!(Query::parse("ubuntu -added:>1y").unwrap().uses_age());
+        assert!(!Query::parse("ubuntu size:>4G ratio:<1").unwrap().uses_age());
+        assert!(!Query::default().uses_age());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Query::parse("\"unfinished"),
+            Err(QueryError::UnterminatedQuote)
+        );
+        assert_eq!(
+            Query::parse("colour:red"),
+            Err(QueryError::UnknownField("colour".to_string()))
+        );
+        assert_eq!(
+            Query::parse("label:"),
+            Err(QueryError::MissingValue("label".to_string()))
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(Comparison::Less.compare(1, 2));
+        assert!(!Comparison::Less.compare(2, 2));
+        assert!(Comparison::LessOrEqual.compare(2, 2));
+        assert!(Comparison::Equal.compare(2.5, 2.5));
+        assert!(Comparison::GreaterOrEqual.compare(3, 2));
+        assert!(!Comparison::Greater.compare(2, 2));
+    }
+}
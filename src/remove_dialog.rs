@@ -0,0 +1,186 @@
+use crate::fl;
+use gtk::prelude::{BoxExt, ButtonExt, Cast, DialogExt, GtkWindowExt, OrientableExt, WidgetExt};
+use gtk::{glib, pango};
+use relm4::ComponentSender;
+use relm4::SimpleComponent;
+use relm4::{gtk, RelmWidgetExt};
+
+pub struct RemoveDialogModel {
+    hashes: Vec<String>,
+    names: String,
+    count: usize,
+    total_size: i64,
+    delete_data: bool,
+    remove_button: gtk::Button,
+    visible: bool,
+}
+
+#[derive(Debug)]
+pub enum RemoveDialogInput {
+    Open(Vec<(String, String)>, i64, bool), // (hash, name) pairs, size on disk, delete data
+    Close,
+    Confirm,
+}
+
+#[derive(Debug)]
+pub enum RemoveDialogOutput {
+    Remove(Vec<String>, bool), // hashes, delete data
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for RemoveDialogModel {
+    type Init = ();
+    type Input = RemoveDialogInput;
+    type Output = RemoveDialogOutput;
+
+    view! {
+        #[root]
+        granite::Dialog {
+            #[watch]
+            set_visible: model.visible,
+            set_modal: true,
+            set_default_width: 400,
+            set_hide_on_close: true,
+
+            connect_response[sender] => move |_, response_type| {
+                if response_type == gtk::ResponseType::Accept {
+                    sender.input(RemoveDialogInput::Confirm);
+                } else {
+                    sender.input(RemoveDialogInput::Close);
+                }
+            },
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_margin_bottom: 12,
+                set_margin_start: 12,
+                set_margin_end: 12,
+                set_margin_top: 12,
+                set_spacing: 12,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 12,
+
+                    gtk::Image {
+                        #[watch]
+                        set_icon_name: Some(if model.delete_data { "edit-delete" } else { "dialog-warning" }),
+                        set_pixel_size: 48,
+                        set_valign: gtk::Align::Start,
+                    },
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 6,
+
+                        gtk::Label {
+                            set_halign: gtk::Align::Start,
+                            set_wrap: true,
+                            set_xalign: 0.0,
+                            add_css_class: granite::STYLE_CLASS_H3_LABEL,
+                            #[watch]
+                            set_label: &if model.delete_data {
+                                fl!("remove-delete-title", count = model.count)
+                            } else {
+                                fl!("remove-title", count = model.count)
+                            },
+                        },
+
+                        gtk::Label {
+                            set_halign: gtk::Align::Start,
+                            set_wrap: true,
+                            set_xalign: 0.0,
+                            #[watch]
+                            set_label: &{
+                                let size = glib::format_size(model.total_size.max(0) as u64).to_string();
+                                if model.delete_data {
+                                    fl!("remove-delete-description", size = size)
+                                } else {
+                                    fl!("remove-description", size = size)
+                                }
+                            },
+                        },
+                    },
+                },
+
+                gtk::ScrolledWindow {
+                    set_propagate_natural_height: true,
+                    set_max_content_height: 150,
+                    add_css_class: granite::STYLE_CLASS_FRAME,
+
+                    gtk::Label {
+                        set_halign: gtk::Align::Start,
+                        set_valign: gtk::Align::Start,
+                        set_margin_all: 6,
+                        set_ellipsize: pango::EllipsizeMode::Middle,
+                        #[watch]
+                        set_label: &model.names,
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> relm4::ComponentParts<Self> {
+        root.add_button(&fl!("action-cancel"), gtk::ResponseType::Cancel);
+        let remove_button = root
+            .add_button(&fl!("action-remove"), gtk::ResponseType::Accept)
+            .downcast::<gtk::Button>()
+            .expect("Dialog buttons are gtk::Button");
+        remove_button.add_css_class("destructive-action");
+
+        let model = RemoveDialogModel {
+            hashes: vec![],
+            names: String::new(),
+            count: 0,
+            total_size: 0,
+            delete_data: false,
+            remove_button,
+            visible: false,
+        };
+
+        let widgets = view_output!();
+
+        relm4::ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            RemoveDialogInput::Open(torrents, total_size, delete_data) => {
+                self.count = torrents.len();
+                self.names = torrents
+                    .iter()
+                    .map(|(_, name)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.hashes = torrents.into_iter().map(|(hash, _)| hash).collect();
+                self.total_size = total_size;
+                self.delete_data = delete_data;
+
+                self.remove_button.set_label(&if delete_data {
+                    fl!("action-remove-and-delete")
+                } else {
+                    fl!("action-remove")
+                });
+
+                self.visible = !self.hashes.is_empty();
+            }
+            RemoveDialogInput::Close => {
+                self.visible = false;
+            }
+            RemoveDialogInput::Confirm => {
+                self.visible = false;
+                sender
+                    .output(RemoveDialogOutput::Remove(
+                        std::mem::take(&mut self.hashes),
+                        self.delete_data,
+                    ))
+                    .unwrap();
+            }
+        }
+    }
+}
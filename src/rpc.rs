@@ -0,0 +1,729 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::Deref;
+
+use isahc::http::StatusCode;
+use isahc::{AsyncReadResponseExt, HttpClient, Request};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use transmission_client::ClientError;
+
+use crate::fl;
+
+/// Where `transmission_client::Client::default()` finds the daemon we start
+const RPC_ADDRESS: &str = "http://127.0.0.1:9091/transmission/rpc/";
+const SESSION_ID_HEADER: &str = "X-Transmission-Session-Id";
+/// The first RPC version (Transmission 4.0) to take `trackerList` in `torrent-set`
+const TRACKER_LIST_RPC_VERSION: i32 = 17;
+/// The first RPC version (Transmission 4.0) to take `labels` in `torrent-add`
+const ADD_LABELS_RPC_VERSION: i32 = 17;
+
+/// Sends the parts of the Transmission RPC that transmission-client doesn't have yet, to the
+/// same daemon
+pub(crate) struct RpcClient {
+    http_client: HttpClient,
+    session_id: RefCell<String>,
+}
+
+#[derive(Serialize)]
+struct RpcRequest<T> {
+    method: &'static str,
+    arguments: T,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: String,
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
+/// `torrent-set` arguments missing from transmission-client's `TorrentMutator`, along with
+/// the ones they're sent together with
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TorrentSetArgs {
+    pub ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_wanted: Option<Vec<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_unwanted: Option<Vec<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_high: Option<Vec<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_normal: Option<Vec<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_low: Option<Vec<i32>>,
+    /// Replaces all of the torrent's labels, so an empty list clears them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    #[serde(rename = "trackerList", skip_serializing_if = "Option::is_none")]
+    pub tracker_list: Option<String>,
+    #[serde(rename = "trackerAdd", skip_serializing_if = "Option::is_none")]
+    pub tracker_add: Option<Vec<String>>,
+    #[serde(rename = "trackerRemove", skip_serializing_if = "Option::is_none")]
+    pub tracker_remove: Option<Vec<i32>>,
+    /// Pairs of tracker id and new announce URL, flattened into one list
+    #[serde(rename = "trackerReplace", skip_serializing_if = "Option::is_none")]
+    pub tracker_replace: Option<Vec<serde_json::Value>>,
+}
+
+/// `torrent-add` arguments, which transmission-client only takes a file or metainfo for
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TorrentAddArgs {
+    /// A path to a .torrent file, or a magnet link
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// A base64 encoded .torrent file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metainfo: Option<String>,
+    /// Where the torrent's data goes, or already is, instead of the default folder
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct TorrentAdded {
+    pub hash_string: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct TorrentAddResponse {
+    torrent_added: Option<TorrentAdded>,
+    torrent_duplicate: Option<TorrentAdded>,
+}
+
+#[derive(Serialize, Debug)]
+struct SessionGetArgs {
+    fields: &'static [&'static str],
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+#[serde(default)]
+struct SessionVersion {
+    rpc_version: i32,
+}
+
+#[derive(Serialize, Debug)]
+struct TorrentGetArgs {
+    fields: &'static [&'static str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ids: Option<Vec<i32>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TorrentGetResponse<T> {
+    torrents: Vec<T>,
+}
+
+/// `transmission_client::Torrent`, along with the fields it doesn't have yet
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct Torrent {
+    #[serde(flatten)]
+    pub torrent: transmission_client::Torrent,
+    pub peers_connected: i32,
+    pub peers_sending_to_us: i32,
+    pub peers_getting_from_us: i32,
+    pub labels: Vec<String>,
+}
+
+impl Torrent {
+    /// The fields transmission-client asks for, then our own
+    const FIELDS: &'static [&'static str] = &[
+        "id",
+        "activityDate",
+        "addedDate",
+        "bandwidthPriority",
+        "comment",
+        "corruptEver",
+        "creator",
+        "dateCreated",
+        "desiredAvailable",
+        "doneDate",
+        "downloadDir",
+        "downloadedEver",
+        "downloadLimit",
+        "downloadLimited",
+        "editDate",
+        "error",
+        "errorString",
+        "eta",
+        "etaIdle",
+        "hashString",
+        "haveUnchecked",
+        "haveValid",
+        "honorsSessionLimits",
+        "isFinished",
+        "isPrivate",
+        "isStalled",
+        "leftUntilDone",
+        "magnetLink",
+        "manualAnnounceTime",
+        "metadataPercentComplete",
+        "name",
+        "percentDone",
+        "pieces",
+        "pieceCount",
+        "pieceSize",
+        "primary-mime-type",
+        "queuePosition",
+        "rateDownload",
+        "rateUpload",
+        "recheckProgress",
+        "secondsDownloading",
+        "secondsSeeding",
+        "seedIdleLimit",
+        "seedIdleMode",
+        "seedRatioLimit",
+        "seedRatioMode",
+        "sizeWhenDone",
+        "startDate",
+        "status",
+        "totalSize",
+        "torrentFile",
+        "uploadedEver",
+        "uploadLimit",
+        "uploadLimited",
+        "uploadRatio",
+        "peersConnected",
+        "peersSendingToUs",
+        "peersGettingFromUs",
+        "labels",
+    ];
+}
+
+impl Deref for Torrent {
+    type Target = transmission_client::Torrent;
+
+    fn deref(&self) -> &Self::Target {
+        &self.torrent
+    }
+}
+
+/// Only what the details dialog's General page shows, which is much less than
+/// `transmission_client::Torrent`
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct TorrentDetails {
+    pub id: i32,
+    pub name: String,
+    pub total_size: i64,
+    pub downloaded_ever: i64,
+    pub uploaded_ever: i64,
+    pub upload_ratio: f32,
+    pub added_date: i32,
+    pub done_date: i32,
+    pub download_dir: String,
+    pub hash_string: String,
+    pub piece_count: i64,
+    pub piece_size: i64,
+    pub comment: String,
+    pub creator: String,
+    pub error_string: String,
+}
+
+impl TorrentDetails {
+    const FIELDS: &'static [&'static str] = &[
+        "id",
+        "name",
+        "totalSize",
+        "downloadedEver",
+        "uploadedEver",
+        "uploadRatio",
+        "addedDate",
+        "doneDate",
+        "downloadDir",
+        "hashString",
+        "pieceCount",
+        "pieceSize",
+        "comment",
+        "creator",
+        "errorString",
+    ];
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct TorrentTrackers {
+    pub id: i32,
+    pub tracker_stats: Vec<TrackerStat>,
+}
+
+impl TorrentTrackers {
+    const FIELDS: &'static [&'static str] = &["id", "trackerStats"];
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct TrackerStat {
+    pub id: i32,
+    pub announce: String,
+    pub tier: i32,
+    pub last_announce_result: String,
+    pub last_scrape_result: String,
+    pub next_announce_time: i64,
+    pub seeder_count: i32,
+    pub leecher_count: i32,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct TorrentPeers {
+    pub id: i32,
+    pub peers: Vec<Peer>,
+    pub peers_from: PeersFrom,
+}
+
+impl TorrentPeers {
+    const FIELDS: &'static [&'static str] = &["id", "peers", "peersFrom"];
+}
+
+/// How many of the connected peers were found each way
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct PeersFrom {
+    pub from_tracker: i32,
+    pub from_dht: i32,
+    pub from_pex: i32,
+    pub from_lpd: i32,
+    pub from_incoming: i32,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct Peer {
+    pub address: String,
+    pub port: i32,
+    pub client_name: String,
+    pub progress: f64,
+    pub rate_to_client: i64,
+    pub rate_to_peer: i64,
+    pub flag_str: String,
+    pub is_encrypted: bool,
+    #[serde(rename = "isUTP")]
+    pub is_utp: bool,
+    pub client_is_choked: bool,
+    pub client_is_interested: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub(crate) struct TorrentPieces {
+    pub id: i32,
+    /// Base64 bitfield of the pieces we have
+    pub pieces: String,
+    /// How many connected peers have each piece, which only Transmission 4 and later send
+    pub availability: Option<Vec<i32>>,
+    pub piece_count: i64,
+    pub piece_size: i64,
+}
+
+impl TorrentPieces {
+    const FIELDS: &'static [&'static str] =
+        &["id", "pieces", "pieceCount", "pieceSize", "availability"];
+}
+
+#[derive(Serialize, Debug)]
+struct TorrentRenamePathArgs {
+    ids: Vec<String>,
+    path: String,
+    name: String,
+}
+
+/// The `trackerReplace`, `trackerRemove` and `trackerAdd` changes for a daemon that doesn't
+/// take `trackerList`
+#[derive(Debug, Default, PartialEq)]
+struct TrackerEdit {
+    replace: Vec<(i32, String)>, // tracker id, new announce URL
+    remove: Vec<i32>,
+    add: Vec<String>,
+}
+
+/// The tiers of a `trackerList`, separated by blank lines
+fn tracker_tiers(tracker_list: &str) -> Vec<Vec<String>> {
+    tracker_list
+        .split('\n')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .split(|line| line.is_empty())
+        .filter(|tier| !tier.is_empty())
+        .map(|tier| tier.iter().map(|url| url.to_string()).collect())
+        .collect()
+}
+
+/// How to turn the `current` trackers into `tracker_list` without `trackerList`. Those daemons
+/// keep a replaced tracker in its tier and put each added one in a new tier of its own, so
+/// this is `None` for any other change of tiers.
+fn tracker_edit(current: &[TrackerStat], tracker_list: &str) -> Option<TrackerEdit> {
+    let wanted = tracker_tiers(tracker_list);
+    let mut current_tiers: BTreeMap<i32, Vec<&TrackerStat>> = BTreeMap::new();
+    for tracker in current {
+        current_tiers.entry(tracker.tier).or_default().push(tracker);
+    }
+    let current_tiers: Vec<Vec<&TrackerStat>> = current_tiers.into_values().collect();
+
+    let is_wanted = |url: &str| wanted.iter().flatten().any(|wanted_url| wanted_url == url);
+    let is_current = |url: &str| current.iter().any(|tracker| tracker.announce == url);
+
+    // Pair up the trackers that went from each tier with the new ones in the same tier
+    let mut edit = TrackerEdit::default();
+    for index in 0..current_tiers.len().max(wanted.len()) {
+        let removed = current_tiers
+            .get(index)
+            .into_iter()
+            .flatten()
+            .filter(|tracker| !is_wanted(&tracker.announce));
+        let mut added = wanted
+            .get(index)
+            .into_iter()
+            .flatten()
+            .filter(|url| !is_current(url));
+
+        for tracker in removed {
+            match added.next() {
+                Some(url) => edit.replace.push((tracker.id, url.clone())),
+                None => edit.remove.push(tracker.id),
+            }
+        }
+        edit.add.extend(added.cloned());
+    }
+
+    // Check that the daemon ends up with the tiers that were asked for
+    let mut result: Vec<Vec<String>> = current_tiers
+        .iter()
+        .map(|tier| {
+            tier.iter()
+                .filter_map(|tracker| {
+                    let replaced = edit.replace.iter().find(|(id, _)| *id == tracker.id);
+                    match replaced {
+                        Some((_, url)) => Some(url.clone()),
+                        None if edit.remove.contains(&tracker.id) => None,
+                        None => Some(tracker.announce.clone()),
+                    }
+                })
+                .collect()
+        })
+        .filter(|tier: &Vec<String>| !tier.is_empty())
+        .collect();
+    result.extend(edit.add.iter().map(|url| vec![url.clone()]));
+
+    let sorted = |mut tiers: Vec<Vec<String>>| {
+        tiers.iter_mut().for_each(|tier| tier.sort());
+        tiers
+    };
+    (sorted(result) == sorted(wanted)).then_some(edit)
+}
+
+/// `torrent-set` can treat an empty list differently from a missing one, e.g. an empty
+/// `files-wanted` means every file, so leave it out
+pub(crate) fn non_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
+
+impl Default for RpcClient {
+    fn default() -> Self {
+        RpcClient {
+            http_client: HttpClient::new().expect("Failed to create HTTP client"),
+            session_id: RefCell::new(String::new()),
+        }
+    }
+}
+
+impl RpcClient {
+    /// Add a torrent, returning it, or the torrent already added with the same hash
+    pub async fn torrent_add(
+        &self,
+        args: TorrentAddArgs,
+    ) -> Result<Option<TorrentAdded>, ClientError> {
+        let labels = args.labels.clone();
+        let response: TorrentAddResponse = self.send("torrent-add", args).await?;
+
+        // Older daemons ignore labels in `torrent-add`, so they're set on the new torrent after
+        if let (Some(labels), Some(added)) = (labels, &response.torrent_added) {
+            if self.rpc_version().await? < ADD_LABELS_RPC_VERSION {
+                let args = TorrentSetArgs {
+                    ids: vec![added.hash_string.clone()],
+                    labels: Some(labels),
+                    ..Default::default()
+                };
+                if let Err(err) = self.torrent_set(args).await {
+                    log::error!("Error setting labels of added torrent: {}", err);
+                }
+            }
+        }
+
+        Ok(response.torrent_added.or(response.torrent_duplicate))
+    }
+
+    pub async fn torrent_set(&self, args: TorrentSetArgs) -> Result<(), ClientError> {
+        let _: serde::de::IgnoredAny = self.send("torrent-set", args).await?;
+        Ok(())
+    }
+
+    /// Replace a torrent's trackers with `tracker_list`, in the `trackerList` format of one
+    /// announce URL per line and a blank line between tiers
+    pub async fn torrent_set_tracker_list(
+        &self,
+        id: i32,
+        hash: String,
+        tracker_list: String,
+    ) -> Result<(), ClientError> {
+        if self.rpc_version().await? >= TRACKER_LIST_RPC_VERSION {
+            let args = TorrentSetArgs {
+                ids: vec![hash],
+                tracker_list: Some(tracker_list),
+                ..Default::default()
+            };
+            return self.torrent_set(args).await;
+        }
+
+        // Older daemons ignore `trackerList`, and can only add, remove or replace trackers
+        let current = self
+            .torrents_trackers(Some(vec![id]))
+            .await?
+            .pop()
+            .map(|trackers| trackers.tracker_stats)
+            .unwrap_or_default();
+        let Some(edit) = tracker_edit(&current, &tracker_list) else {
+            return Err(ClientError::TransmissionError(fl!(
+                "details-tracker-tiers-unsupported"
+            )));
+        };
+
+        let args = TorrentSetArgs {
+            ids: vec![hash],
+            tracker_replace: non_empty(
+                edit.replace
+                    .into_iter()
+                    .flat_map(|(id, url)| [serde_json::json!(id), serde_json::json!(url)])
+                    .collect(),
+            ),
+            tracker_remove: non_empty(edit.remove),
+            tracker_add: non_empty(edit.add),
+            ..Default::default()
+        };
+        self.torrent_set(args).await
+    }
+
+    /// Rename a file or folder, or the torrent itself when `path` is its name
+    pub async fn torrent_rename_path(
+        &self,
+        hash: String,
+        path: String,
+        name: String,
+    ) -> Result<(), ClientError> {
+        let args = TorrentRenamePathArgs {
+            ids: vec![hash],
+            path,
+            name,
+        };
+        let _: serde::de::IgnoredAny = self.send("torrent-rename-path", args).await?;
+        Ok(())
+    }
+
+    pub async fn torrents(&self, ids: Option<Vec<i32>>) -> Result<Vec<Torrent>, ClientError> {
+        self.torrent_get(Torrent::FIELDS, ids).await
+    }
+
+    pub async fn torrents_details(
+        &self,
+        ids: Vec<i32>,
+    ) -> Result<Vec<TorrentDetails>, ClientError> {
+        self.torrent_get(TorrentDetails::FIELDS, Some(ids)).await
+    }
+
+    pub async fn torrents_trackers(
+        &self,
+        ids: Option<Vec<i32>>,
+    ) -> Result<Vec<TorrentTrackers>, ClientError> {
+        self.torrent_get(TorrentTrackers::FIELDS, ids).await
+    }
+
+    pub async fn torrents_peers(&self, ids: Vec<i32>) -> Result<Vec<TorrentPeers>, ClientError> {
+        self.torrent_get(TorrentPeers::FIELDS, Some(ids)).await
+    }
+
+    pub async fn torrents_pieces(&self, ids: Vec<i32>) -> Result<Vec<TorrentPieces>, ClientError> {
+        self.torrent_get(TorrentPieces::FIELDS, Some(ids)).await
+    }
+
+    async fn rpc_version(&self) -> Result<i32, ClientError> {
+        let session: SessionVersion = self
+            .send(
+                "session-get",
+                SessionGetArgs {
+                    fields: &["rpc-version"],
+                },
+            )
+            .await?;
+        Ok(session.rpc_version)
+    }
+
+    async fn torrent_get<T: DeserializeOwned>(
+        &self,
+        fields: &'static [&'static str],
+        ids: Option<Vec<i32>>,
+    ) -> Result<Vec<T>, ClientError> {
+        let response: TorrentGetResponse<T> = self
+            .send("torrent-get", TorrentGetArgs { fields, ids })
+            .await?;
+        Ok(response.torrents)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        arguments: impl Serialize,
+    ) -> Result<T, ClientError> {
+        let body = serde_json::to_string(&RpcRequest { method, arguments })?;
+
+        let mut response = self.http_client.send_async(self.request(&body)?).await?;
+        // The daemon turns away the first request with the session id to use from then on
+        if response.status() == StatusCode::CONFLICT {
+            if let Some(session_id) = response.headers().get(SESSION_ID_HEADER) {
+                *self.session_id.borrow_mut() = session_id.to_str().unwrap_or_default().into();
+            }
+            response = self.http_client.send_async(self.request(&body)?).await?;
+        }
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(ClientError::TransmissionUnauthorized);
+        }
+
+        let text = response.text().await.map_err(isahc::Error::from)?;
+        let response: RpcResponse = serde_json::from_str(&text)?;
+        if response.result != "success" {
+            return Err(ClientError::TransmissionError(response.result));
+        }
+
+        Ok(serde_json::from_value(response.arguments)?)
+    }
+
+    fn request(&self, body: &str) -> Result<Request<String>, ClientError> {
+        Ok(Request::post(RPC_ADDRESS)
+            .header(SESSION_ID_HEADER, self.session_id.borrow().as_str())
+            .body(body.to_string())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(id: i32, tier: i32, announce: &str) -> TrackerStat {
+        TrackerStat {
+            id,
+            tier,
+            announce: announce.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn current() -> Vec<TrackerStat> {
+        vec![
+            tracker(0, 0, "https://a.example/announce"),
+            tracker(1, 0, "https://b.example/announce"),
+            tracker(2, 1, "udp://c.example:80"),
+        ]
+    }
+
+    #[test]
+    fn tiers_are_split_on_blank_lines() {
+        assert_eq!(
+            tracker_tiers("a\n b \n\n\n c\n\n"),
+            vec![vec!["a", "b"], vec!["c"]]
+        );
+        assert!(tracker_tiers("\n \n").is_empty());
+    }
+
+    #[test]
+    fn unchanged_list_needs_no_edit() {
+        let list = "https://b.example/announce\nhttps://a.example/announce\n\nudp://c.example:80";
+        assert_eq!(tracker_edit(&current(), list), Some(TrackerEdit::default()));
+    }
+
+    #[test]
+    fn replacing_keeps_the_tier() {
+        let list = "https://a.example/announce\nhttps://b.example/announce\n\nudp://d.example:80";
+        assert_eq!(
+            tracker_edit(&current(), list),
+            Some(TrackerEdit {
+                replace: vec![(2, "udp://d.example:80".to_string())],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn removing_and_adding_a_tier() {
+        let list = "https://a.example/announce\n\nudp://c.example:80\n\nhttps://d.example/announce";
+        assert_eq!(
+            tracker_edit(&current(), list),
+            Some(TrackerEdit {
+                remove: vec![1],
+                add: vec!["https://d.example/announce".to_string()],
+                ..Default::default()
+            })
+        );
+
+        let list = "https://a.example/announce\nhttps://b.example/announce";
+        assert_eq!(
+            tracker_edit(&current(), list),
+            Some(TrackerEdit {
+                remove: vec![2],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn tier_changes_are_rejected() {
+        // Moving a tracker to another tier
+        let list = "https://a.example/announce\n\nhttps://b.example/announce\nudp://c.example:80";
+        assert_eq!(tracker_edit(&current(), list), None);
+
+        // Merging tiers
+        let list = "https://a.example/announce\nhttps://b.example/announce\nudp://c.example:80";
+        assert_eq!(tracker_edit(&current(), list), None);
+
+        // Adding to an existing tier, or two trackers in one new tier
+        let list = "https://a.example/announce\nhttps://b.example/announce\nhttps://d.example/announce\n\nudp://c.example:80";
+        assert_eq!(tracker_edit(&current(), list), None);
+        let list = "https://a.example/announce\nhttps://b.example/announce\n\nudp://c.example:80\n\nhttps://d.example/announce\nhttps://e.example/announce";
+        assert_eq!(tracker_edit(&current(), list), None);
+    }
+
+    #[test]
+    fn trackers_can_be_added_to_a_torrent_without_any() {
+        let list = "https://a.example/announce\n\nhttps://b.example/announce";
+        assert_eq!(
+            tracker_edit(&[], list),
+            Some(TrackerEdit {
+                add: vec![
+                    "https://a.example/announce".to_string(),
+                    "https://b.example/announce".to_string()
+                ],
+                ..Default::default()
+            })
+        );
+    }
+}
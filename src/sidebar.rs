@@ -0,0 +1,641 @@
+use crate::fl;
+use crate::torrent::{TorrentObject, TorrentState};
+use crate::views::SavedView;
+use gtk::prelude::{
+    ActionableExt, BoxExt, ButtonExt, GestureSingleExt, OrientableExt, PopoverExt, ToVariant,
+    WidgetExt,
+};
+use gtk::{gio, pango, prelude::ListBoxRowExt};
+use relm4::actions::ActionName;
+use relm4::gtk;
+use relm4::ComponentSender;
+use relm4::SimpleComponent;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// What the torrent list is narrowed down to. Stored as its id in the `filter` action's state
+/// and in settings, so the choice survives a restart.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TorrentFilter {
+    All,
+    Downloading,
+    Seeding,
+    Paused,
+    Queued,
+    Checking,
+    Errors,
+    Active,
+    Inactive,
+    Complete,
+    Incomplete,
+    Tracker(String), // tracker domain
+    Folder(String),  // download directory
+    View(String),    // saved view name
+}
+
+impl TorrentFilter {
+    pub const STATES: [TorrentFilter; 11] = [
+        TorrentFilter::All,
+        TorrentFilter::Downloading,
+        TorrentFilter::Seeding,
+        TorrentFilter::Paused,
+        TorrentFilter::Queued,
+        TorrentFilter::Checking,
+        TorrentFilter::Errors,
+        TorrentFilter::Active,
+        TorrentFilter::Inactive,
+        TorrentFilter::Complete,
+        TorrentFilter::Incomplete,
+    ];
+
+    pub fn id(&self) -> String {
+        match self {
+            TorrentFilter::All => "all".to_string(),
+            TorrentFilter::Downloading => "downloading".to_string(),
+            TorrentFilter::Seeding => "seeding".to_string(),
+            TorrentFilter::Paused => "paused".to_string(),
+            TorrentFilter::Queued => "queued".to_string(),
+            TorrentFilter::Checking => "checking".to_string(),
+            TorrentFilter::Errors => "errors".to_string(),
+            TorrentFilter::Active => "active".to_string(),
+            TorrentFilter::Inactive => "inactive".to_string(),
+            TorrentFilter::Complete => "complete".to_string(),
+            TorrentFilter::Incomplete => "incomplete".to_string(),
+            TorrentFilter::Tracker(domain) => format!("tracker:{}", domain),
+            TorrentFilter::Folder(folder) => format!("folder:{}", folder),
+            TorrentFilter::View(name) => format!("view:{}", name),
+        }
+    }
+
+    /// The filter with the given id, or all torrents if the id isn't recognised
+    pub fn from_id(id: &str) -> Self {
+        if let Some(domain) = id.strip_prefix("tracker:") {
+            return TorrentFilter::Tracker(domain.to_string());
+        }
+        if let Some(folder) = id.strip_prefix("folder:") {
+            return TorrentFilter::Folder(folder.to_string());
+        }
+        if let Some(name) = id.strip_prefix("view:") {
+            return TorrentFilter::View(name.to_string());
+        }
+
+        TorrentFilter::STATES
+            .into_iter()
+            .find(|filter| filter.id() == id)
+            .unwrap_or(TorrentFilter::All)
+    }
+
+    fn label(&self) -> String {
+        match self {
+            TorrentFilter::All => fl!("filter-all"),
+            TorrentFilter::Downloading => fl!("filter-downloading"),
+            TorrentFilter::Seeding => fl!("filter-seeding"),
+            TorrentFilter::Paused => fl!("filter-paused"),
+            TorrentFilter::Queued => fl!("filter-queued"),
+            TorrentFilter::Checking => fl!("filter-checking"),
+            TorrentFilter::Errors => fl!("filter-errors"),
+            TorrentFilter::Active => fl!("filter-active"),
+            TorrentFilter::Inactive => fl!("filter-inactive"),
+            TorrentFilter::Complete => fl!("filter-complete"),
+            TorrentFilter::Incomplete => fl!("filter-incomplete"),
+            TorrentFilter::Tracker(domain) => domain.clone(),
+            TorrentFilter::Folder(folder) => Path::new(folder)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| folder.clone()),
+            TorrentFilter::View(name) => name.clone(),
+        }
+    }
+
+    /// Saved views always match here, as they're made of other filters and a search. The
+    /// caller looks the view up and uses its `ViewMatcher` as well.
+    pub fn matches(&self, torrent: &TorrentObject) -> bool {
+        let state = torrent.state();
+        match self {
+            TorrentFilter::All => true,
+            TorrentFilter::Downloading => {
+                state == TorrentState::Downloading || state == TorrentState::DownloadWaiting
+            }
+            TorrentFilter::Seeding => {
+                state == TorrentState::Seeding || state == TorrentState::SeedWaiting
+            }
+            TorrentFilter::Paused => state == TorrentState::Stopped,
+            TorrentFilter::Queued => {
+                state == TorrentState::DownloadWaiting || state == TorrentState::SeedWaiting
+            }
+            TorrentFilter::Checking => {
+                state == TorrentState::CheckWaiting || state == TorrentState::Checking
+            }
+            TorrentFilter::Errors => torrent.error() != 0,
+            TorrentFilter::Active => is_active(torrent),
+            TorrentFilter::Inactive => !is_active(torrent),
+            TorrentFilter::Complete => torrent.data().percent_done >= 1.0,
+            TorrentFilter::Incomplete => torrent.data().percent_done < 1.0,
+            TorrentFilter::Tracker(domain) => torrent.tracker_domains().contains(domain),
+            TorrentFilter::Folder(folder) => {
+                torrent.download_dir().trim_end_matches('/') == folder.as_str()
+            }
+            TorrentFilter::View(_) => true,
+        }
+    }
+}
+
+/// Whether data is moving in either direction, or the local data is being checked
+fn is_active(torrent: &TorrentObject) -> bool {
+    let state = torrent.state();
+    let data = torrent.data();
+    data.rate_download > 0 || data.rate_upload > 0 || state == TorrentState::Checking
+}
+
+/// The part of a tracker's announce URL that says who runs it, e.g. `opentrackr.org` for
+/// `udp://tracker.opentrackr.org:1337/announce`
+pub fn tracker_domain(announce: &str) -> Option<String> {
+    let rest = announce
+        .split_once("://")
+        .map_or(announce, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host_and_port.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next()?,
+        None => host_and_port.split(':').next()?,
+    };
+
+    if host.is_empty() {
+        return None;
+    }
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return Some(host.to_string());
+    }
+
+    // Keep the registered domain, allowing for country domains like .co.uk
+    let parts: Vec<&str> = host.split('.').filter(|part| !part.is_empty()).collect();
+    let second_level = ["ac", "co", "com", "edu", "gov", "net", "org"];
+    let keep = if parts.len() > 2
+        && parts[parts.len() - 1].len() == 2
+        && second_level.contains(&parts[parts.len() - 2])
+    {
+        3
+    } else {
+        2
+    };
+
+    Some(
+        parts[parts.len().saturating_sub(keep)..]
+            .join(".")
+            .to_lowercase(),
+    )
+}
+
+/// Every filter in the sidebar and how many torrents it matches. The states are always
+/// listed, then the saved views, then each tracker domain and download folder in use.
+/// `selected` is included even if no torrent matches it any more, so a restored choice
+/// doesn't silently disappear.
+fn filter_counts(
+    torrents: &[TorrentObject],
+    views: &[SavedView],
+    selected: &TorrentFilter,
+) -> Vec<(TorrentFilter, usize)> {
+    let mut counts: Vec<(TorrentFilter, usize)> = TorrentFilter::STATES
+        .into_iter()
+        .map(|filter| {
+            let count = torrents
+                .iter()
+                .filter(|torrent| filter.matches(torrent))
+                .count();
+            (filter, count)
+        })
+        .collect();
+
+    let now = gtk::glib::DateTime::now_utc()
+        .map(|now| now.to_unix())
+        .unwrap_or_default();
+    counts.extend(views.iter().map(|view| {
+        let matcher = view.matcher();
+        let count = torrents
+            .iter()
+            .filter(|torrent| matcher.matches(torrent, now))
+            .count();
+        (TorrentFilter::View(view.name.clone()), count)
+    }));
+
+    let mut trackers: BTreeMap<String, usize> = BTreeMap::new();
+    let mut folders: BTreeMap<String, usize> = BTreeMap::new();
+    for torrent in torrents {
+        for domain in torrent.tracker_domains() {
+            *trackers.entry(domain).or_default() += 1;
+        }
+        let folder = torrent.download_dir().trim_end_matches('/').to_string();
+        *folders.entry(folder).or_default() += 1;
+    }
+
+    match selected {
+        TorrentFilter::Tracker(domain) => {
+            trackers.entry(domain.clone()).or_default();
+        }
+        TorrentFilter::Folder(folder) => {
+            folders.entry(folder.clone()).or_default();
+        }
+        _ => {}
+    }
+
+    counts.extend(
+        trackers
+            .into_iter()
+            .map(|(domain, count)| (TorrentFilter::Tracker(domain), count)),
+    );
+    counts.extend(
+        folders
+            .into_iter()
+            .map(|(folder, count)| (TorrentFilter::Folder(folder), count)),
+    );
+    counts
+}
+
+pub struct SidebarModel {
+    list: gtk::ListBox,
+    count_labels: Vec<(TorrentFilter, gtk::Label)>,
+    selected: TorrentFilter,
+    torrents: Vec<TorrentObject>,
+    views: Vec<SavedView>,
+    view_popover: gtk::PopoverMenu,
+}
+
+#[derive(Debug)]
+pub enum SidebarInput {
+    TorrentsChanged(Vec<TorrentObject>),
+    ViewsChanged(Vec<SavedView>),
+    Select(String), // filter id
+    ShowViewMenu(f64, f64),
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for SidebarModel {
+    type Init = String; // the filter id to start with
+    type Input = SidebarInput;
+    type Output = ();
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_width_request: 180,
+
+            gtk::ScrolledWindow {
+                set_vexpand: true,
+                set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                #[local_ref]
+                list -> gtk::ListBox {
+                    add_css_class: "navigation-sidebar",
+                    set_selection_mode: gtk::SelectionMode::Single,
+
+                    add_controller = gtk::GestureClick {
+                        set_button: gtk::gdk::BUTTON_SECONDARY,
+                        connect_released[sender] => move |_, _, x, y| {
+                            sender.input(SidebarInput::ShowViewMenu(x, y));
+                        }
+                    }
+                }
+            },
+
+            gtk::ActionBar {
+                pack_start = &gtk::Button {
+                    set_icon_name: "list-add-symbolic",
+                    set_tooltip_text: Some(&fl!("action-save-view")),
+                    add_css_class: "flat",
+                    set_action_name: Some(&crate::SaveViewAction::action_name()),
+                },
+            },
+        }
+    }
+
+    fn init(
+        filter_id: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> relm4::ComponentParts<Self> {
+        let view_popover = gtk::PopoverMenu::builder()
+            .halign(gtk::Align::Start)
+            .has_arrow(false)
+            .position(gtk::PositionType::Bottom)
+            .build();
+        view_popover.set_parent(&root);
+
+        let model = SidebarModel {
+            list: gtk::ListBox::new(),
+            count_labels: vec![],
+            selected: TorrentFilter::from_id(&filter_id),
+            torrents: vec![],
+            views: crate::views::saved_views(),
+            view_popover,
+        };
+
+        let list = &model.list;
+        let widgets = view_output!();
+
+        relm4::ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
+        match message {
+            SidebarInput::TorrentsChanged(torrents) => {
+                self.torrents = torrents;
+                self.refresh();
+            }
+            SidebarInput::ViewsChanged(views) => {
+                self.views = views;
+                self.refresh();
+            }
+            SidebarInput::Select(filter_id) => {
+                self.selected = TorrentFilter::from_id(&filter_id);
+                self.select_current();
+            }
+            SidebarInput::ShowViewMenu(x, y) => {
+                let Some(row) = self.list.row_at_y(y as i32) else {
+                    return;
+                };
+                let Some((TorrentFilter::View(name), _)) =
+                    self.count_labels.get(row.index() as usize)
+                else {
+                    return;
+                };
+
+                let view_menu = gio::Menu::new();
+                let actions = [
+                    (fl!("action-rename"), crate::RenameViewAction::action_name()),
+                    (fl!("view-move-up"), crate::MoveViewUpAction::action_name()),
+                    (
+                        fl!("view-move-down"),
+                        crate::MoveViewDownAction::action_name(),
+                    ),
+                    (
+                        fl!("action-delete-view"),
+                        crate::DeleteViewAction::action_name(),
+                    ),
+                ];
+                for (label, action_name) in actions {
+                    let item = gio::MenuItem::new(Some(&label), None);
+                    item.set_action_and_target_value(Some(&action_name), Some(&name.to_variant()));
+                    view_menu.append_item(&item);
+                }
+
+                // The menu hangs off the sidebar box, so point at the click from there
+                let (x, y) = self
+                    .view_popover
+                    .parent()
+                    .and_then(|parent| self.list.translate_coordinates(&parent, x, y))
+                    .unwrap_or((x, y));
+                let rect = gtk::gdk::Rectangle::new(x as i32, y as i32, 0, 0);
+                self.view_popover.set_pointing_to(Some(&rect));
+                self.view_popover.set_menu_model(Some(&view_menu));
+                self.view_popover.popup();
+            }
+        }
+    }
+}
+
+impl SidebarModel {
+    fn refresh(&mut self) {
+        let counts = filter_counts(&self.torrents, &self.views, &self.selected);
+
+        // Only rebuild the rows when views, trackers or folders come and go, so that the
+        // periodic refresh doesn't fight with the user's clicks
+        let same_filters = counts.len() == self.count_labels.len()
+            && counts
+                .iter()
+                .zip(&self.count_labels)
+                .all(|((filter, _), (existing, _))| filter == existing);
+        if same_filters {
+            for ((_, count), (_, label)) in counts.iter().zip(&self.count_labels) {
+                label.set_text(&count.to_string());
+            }
+        } else {
+            self.rebuild_rows(counts);
+        }
+    }
+
+    fn rebuild_rows(&mut self, counts: Vec<(TorrentFilter, usize)>) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+        self.count_labels.clear();
+
+        let mut previous_group = None;
+        for (filter, count) in counts {
+            let group = match filter {
+                TorrentFilter::View(_) => Some(fl!("sidebar-views")),
+                TorrentFilter::Tracker(_) => Some(fl!("sidebar-trackers")),
+                TorrentFilter::Folder(_) => Some(fl!("sidebar-folders")),
+                _ => None,
+            };
+
+            let name_label = gtk::Label::new(Some(&filter.label()));
+            name_label.set_hexpand(true);
+            name_label.set_xalign(0.0);
+            name_label.set_ellipsize(pango::EllipsizeMode::End);
+
+            let count_label = gtk::Label::new(Some(&count.to_string()));
+            count_label.add_css_class("dim-label");
+            count_label.add_css_class("numeric");
+
+            let row_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            row_box.append(&name_label);
+            row_box.append(&count_label);
+
+            let row = gtk::ListBoxRow::new();
+            row.set_child(Some(&row_box));
+            row.set_action_name(Some(&crate::FilterAction::action_name()));
+            row.set_action_target_value(Some(&filter.id().to_variant()));
+            if let TorrentFilter::Folder(folder) = &filter {
+                row.set_tooltip_text(Some(folder));
+            }
+
+            if group.is_some() && group != previous_group {
+                let header = gtk::Label::new(group.as_deref());
+                header.set_xalign(0.0);
+                header.set_margin_top(12);
+                header.set_margin_start(6);
+                header.add_css_class(granite::STYLE_CLASS_SMALL_LABEL);
+                header.add_css_class("dim-label");
+                row.set_header(Some(&header));
+            }
+            previous_group = group;
+
+            self.list.append(&row);
+            self.count_labels.push((filter, count_label));
+        }
+
+        self.select_current();
+    }
+
+    fn select_current(&self) {
+        let row = self
+            .count_labels
+            .iter()
+            .position(|(filter, _)| *filter == self.selected)
+            .and_then(|index| self.list.row_at_index(index as i32));
+        self.list.select_row(row.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i18n_embed::LanguageLoader;
+
+    fn torrent(state: TorrentState, download_dir: &str, domains: &[&str]) -> TorrentObject {
+        crate::STATIC_LANGUAGE_LOADER
+            .load_fallback_language(&crate::Localizations)
+            .unwrap();
+
+        let torrent = TorrentObject::new(crate::rpc::Torrent {
+            torrent: transmission_client::Torrent {
+                status: state as i32,
+                download_dir: download_dir.to_string(),
+                total_size: 2_000_000_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        torrent.set_tracker_domains(domains.iter().map(|domain| domain.to_string()).collect());
+        torrent
+    }
+
+    fn domain(announce: &str) -> Option<String> {
+        tracker_domain(announce)
+    }
+
+    #[test]
+    fn tracker_domains_keep_the_registered_domain() {
+        assert_eq!(
+            domain("udp://tracker.opentrackr.org:1337/announce").as_deref(),
+            Some("opentrackr.org")
+        );
+        assert_eq!(
+            domain("HTTPS://Tracker.Example.ORG/announce?passkey=abc#top").as_deref(),
+            Some("example.org")
+        );
+        assert_eq!(
+            domain("tracker.example.net:80/announce").as_deref(),
+            Some("example.net")
+        );
+        assert_eq!(domain("http://example.io/").as_deref(), Some("example.io"));
+    }
+
+    #[test]
+    fn tracker_domains_allow_for_second_level_country_domains() {
+        assert_eq!(
+            domain("https://tracker.example.co.uk/announce").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            domain("http://bt.example.com.au:6969/announce").as_deref(),
+            Some("example.com.au")
+        );
+    }
+
+    #[test]
+    fn tracker_domains_of_addresses_and_userinfo() {
+        assert_eq!(
+            domain("udp://[2001:db8::1]:6969/announce").as_deref(),
+            Some("2001:db8::1")
+        );
+        assert_eq!(
+            domain("http://192.168.1.10:6969/announce").as_deref(),
+            Some("192.168.1.10")
+        );
+        assert_eq!(
+            domain("https://user:p@ss@tracker.example.org:443/announce").as_deref(),
+            Some("example.org")
+        );
+    }
+
+    #[test]
+    fn trackers_without_a_host_have_no_domain() {
+        assert_eq!(domain(""), None);
+        assert_eq!(domain("udp:///announce"), None);
+        assert_eq!(domain("http://user@:80/announce"), None);
+    }
+
+    #[test]
+    fn filter_ids_round_trip() {
+        let filters = TorrentFilter::STATES.into_iter().chain([
+            TorrentFilter::Tracker("example.org".to_string()),
+            TorrentFilter::Tracker("2001:db8::1".to_string()),
+            TorrentFilter::Folder("/home/me/Downloads".to_string()),
+            TorrentFilter::View("Films: big ones".to_string()),
+        ]);
+        for filter in filters {
+            assert_eq!(TorrentFilter::from_id(&filter.id()), filter);
+        }
+    }
+
+    #[test]
+    fn unknown_filter_ids_show_everything() {
+        assert_eq!(TorrentFilter::from_id(""), TorrentFilter::All);
+        assert_eq!(TorrentFilter::from_id("label:films"), TorrentFilter::All);
+        assert_eq!(TorrentFilter::from_id("Downloading"), TorrentFilter::All);
+    }
+
+    #[test]
+    fn filters_are_counted() {
+        let torrents = vec![
+            torrent(TorrentState::Downloading, "/downloads/", &["example.org"]),
+            torrent(
+                TorrentState::Seeding,
+                "/downloads",
+                &["example.org", "other.net"],
+            ),
+            torrent(TorrentState::Stopped, "/films", &[]),
+        ];
+        let views = vec![SavedView {
+            name: "Paused".to_string(),
+            filter: "paused".to_string(),
+            label: String::new(),
+            query: "size:>1G".to_string(),
+        }];
+
+        let counts = filter_counts(&torrents, &views, &TorrentFilter::All);
+        let count = |filter: TorrentFilter| {
+            counts
+                .iter()
+                .find(|(counted, _)| *counted == filter)
+                .map(|(_, count)| *count)
+        };
+
+        assert_eq!(count(TorrentFilter::All), Some(3));
+        assert_eq!(count(TorrentFilter::Downloading), Some(1));
+        assert_eq!(count(TorrentFilter::Seeding), Some(1));
+        assert_eq!(count(TorrentFilter::Paused), Some(1));
+        assert_eq!(count(TorrentFilter::Errors), Some(0));
+        assert_eq!(count(TorrentFilter::View("Paused".to_string())), Some(1));
+
+        // States first, then views, then trackers and folders in alphabetical order
+        let rest: Vec<(TorrentFilter, usize)> = counts[TorrentFilter::STATES.len()..].to_vec();
+        assert_eq!(
+            rest,
+            vec![
+                (TorrentFilter::View("Paused".to_string()), 1),
+                (TorrentFilter::Tracker("example.org".to_string()), 2),
+                (TorrentFilter::Tracker("other.net".to_string()), 1),
+                (TorrentFilter::Folder("/downloads".to_string()), 2),
+                (TorrentFilter::Folder("/films".to_string()), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn selected_filter_is_kept_without_torrents() {
+        let torrents = vec![torrent(TorrentState::Stopped, "/films", &[])];
+
+        let selected = TorrentFilter::Folder("/gone".to_string());
+        let counts = filter_counts(&torrents, &[], &selected);
+        assert!(counts.contains(&(selected, 0)));
+
+        let selected = TorrentFilter::Tracker("gone.org".to_string());
+        let counts = filter_counts(&torrents, &[], &selected);
+        assert!(counts.contains(&(selected, 0)));
+    }
+}
@@ -0,0 +1,316 @@
+use crate::fl;
+use crate::rpc::Torrent;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// What the torrent list is sorted by, stored in the `sort-by` setting by its id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Progress,
+    Size,
+    DateAdded,
+    DownloadSpeed,
+    UploadSpeed,
+    Ratio,
+    Eta,
+    QueuePosition,
+    State,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 10] = [
+        SortKey::Name,
+        SortKey::Progress,
+        SortKey::Size,
+        SortKey::DateAdded,
+        SortKey::DownloadSpeed,
+        SortKey::UploadSpeed,
+        SortKey::Ratio,
+        SortKey::Eta,
+        SortKey::QueuePosition,
+        SortKey::State,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Progress => "progress",
+            SortKey::Size => "size",
+            SortKey::DateAdded => "date-added",
+            SortKey::DownloadSpeed => "download-speed",
+            SortKey::UploadSpeed => "upload-speed",
+            SortKey::Ratio => "ratio",
+            SortKey::Eta => "eta",
+            SortKey::QueuePosition => "queue-position",
+            SortKey::State => "state",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|key| key.id() == id)
+            .unwrap_or(SortKey::QueuePosition)
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            SortKey::Name => fl!("sort-name"),
+            SortKey::Progress => fl!("sort-progress"),
+            SortKey::Size => fl!("sort-size"),
+            SortKey::DateAdded => fl!("sort-date-added"),
+            SortKey::DownloadSpeed => fl!("sort-download-speed"),
+            SortKey::UploadSpeed => fl!("sort-upload-speed"),
+            SortKey::Ratio => fl!("sort-ratio"),
+            SortKey::Eta => fl!("sort-eta"),
+            SortKey::QueuePosition => fl!("sort-queue-position"),
+            SortKey::State => fl!("sort-state"),
+        }
+    }
+
+    /// Rates, progress and times change on every refresh, so they're compared in coarse steps
+    /// to stop rows jumping around for small changes
+    pub fn compare(&self, a: &Torrent, b: &Torrent) -> Ordering {
+        match self {
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortKey::Progress => percent_step(a.percent_done).cmp(&percent_step(b.percent_done)),
+            SortKey::Size => a.total_size.cmp(&b.total_size),
+            SortKey::DateAdded => a.added_date.cmp(&b.added_date),
+            SortKey::DownloadSpeed => {
+                rate_step(a.rate_download as i64).cmp(&rate_step(b.rate_download as i64))
+            }
+            SortKey::UploadSpeed => {
+                rate_step(a.rate_upload as i64).cmp(&rate_step(b.rate_upload as i64))
+            }
+            SortKey::Ratio => percent_step(a.upload_ratio).cmp(&percent_step(b.upload_ratio)),
+            SortKey::Eta => eta_step(a.eta).cmp(&eta_step(b.eta)),
+            SortKey::QueuePosition => a.queue_position.cmp(&b.queue_position),
+            SortKey::State => a.status.cmp(&b.status),
+        }
+    }
+}
+
+fn percent_step(fraction: f32) -> i64 {
+    (fraction * 100.0).floor() as i64
+}
+
+/// Speeds grouped into steps that double in size, so 1.1 MB/s and 1.2 MB/s sort together
+fn rate_step(rate: i64) -> u32 {
+    (rate.max(0) as u64 / 1024 + 1).ilog2()
+}
+
+/// Unknown ETAs are negative, and sort after every known one
+fn eta_step(eta: i64) -> i64 {
+    if eta < 0 {
+        i64::MAX
+    } else {
+        eta / 60
+    }
+}
+
+/// Compare two of the daemon's torrents for display. Torrents that compare equal stay in the
+/// order they were last shown in, which `previous_order` maps from hash to row.
+pub fn compare_torrents(
+    a: &Torrent,
+    b: &Torrent,
+    key: SortKey,
+    ascending: bool,
+    previous_order: &HashMap<String, usize>,
+) -> Ordering {
+    let previous_position = |torrent: &Torrent| {
+        previous_order
+            .get(&torrent.hash_string)
+            .copied()
+            .unwrap_or(usize::MAX)
+    };
+
+    let ordering = key.compare(a, b);
+    let ordering = if ascending {
+        ordering
+    } else {
+        ordering.reverse()
+    };
+
+    ordering
+        .then_with(|| previous_position(a).cmp(&previous_position(b)))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(id: i32, name: &str) -> Torrent {
+        Torrent {
+            torrent: transmission_client::Torrent {
+                id,
+                name: name.to_string(),
+                hash_string: format!("hash-{}", id),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn compare(a: &Torrent, b: &Torrent, key: SortKey) -> Ordering {
+        compare_torrents(a, b, key, true, &HashMap::new())
+    }
+
+    /// Two torrents where `a` sorts first by `key` ascending
+    fn ordered_pair(key: SortKey) -> (Torrent, Torrent) {
+        let mut a = torrent(2, "b");
+        let mut b = torrent(1, "a");
+        match key {
+            SortKey::Name => {
+                a.torrent.name = "Alpha".to_string();
+                b.torrent.name = "beta".to_string();
+            }
+            SortKey::Progress => {
+                a.torrent.percent_done = 0.25;
+                b.torrent.percent_done = 0.5;
+            }
+            SortKey::Size => {
+                a.torrent.total_size = 1024;
+                b.torrent.total_size = 2048;
+            }
+            SortKey::DateAdded => {
+                a.torrent.added_date = 1000;
+                b.torrent.added_date = 2000;
+            }
+            SortKey::DownloadSpeed => {
+                a.torrent.rate_download = 1024;
+                b.torrent.rate_download = 1024 * 1024;
+            }
+            SortKey::UploadSpeed => {
+                a.torrent.rate_upload = 1024;
+                b.torrent.rate_upload = 1024 * 1024;
+            }
+            SortKey::Ratio => {
+                a.torrent.upload_ratio = 0.5;
+                b.torrent.upload_ratio = 1.5;
+            }
+            SortKey::Eta => {
+                a.torrent.eta = 60;
+                b.torrent.eta = 3600;
+            }
+            SortKey::QueuePosition => {
+                a.torrent.queue_position = 0;
+                b.torrent.queue_position = 1;
+            }
+            SortKey::State => {
+                a.torrent.status = 0;
+                b.torrent.status = 4;
+            }
+        }
+        (a, b)
+    }
+
+    #[test]
+    fn sorts_by_each_key() {
+        for key in SortKey::ALL {
+            let (a, b) = ordered_pair(key);
+            assert_eq!(compare(&a, &b, key), Ordering::Less, "{:?}", key);
+            assert_eq!(compare(&b, &a, key), Ordering::Greater, "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn descending_reverses_each_key() {
+        for key in SortKey::ALL {
+            let (a, b) = ordered_pair(key);
+            assert_eq!(
+                compare_torrents(&a, &b, key, false, &HashMap::new()),
+                Ordering::Greater,
+                "{:?}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn names_ignore_case() {
+        let a = torrent(1, "ubuntu");
+        let b = torrent(2, "Ubuntu");
+        // Equal names fall back to the id
+        assert_eq!(compare(&a, &b, SortKey::Name), Ordering::Less);
+    }
+
+    #[test]
+    fn small_changes_compare_equal() {
+        // The lower id has the larger raw values, so only the steps and then the id tie-break
+        // put it first
+        let mut a = torrent(1, "a");
+        let mut b = torrent(2, "b");
+        a.torrent.rate_download = 1200 * 1024;
+        b.torrent.rate_download = 1100 * 1024;
+        a.torrent.rate_upload = 1200 * 1024;
+        b.torrent.rate_upload = 1100 * 1024;
+        a.torrent.percent_done = 0.509;
+        b.torrent.percent_done = 0.501;
+        a.torrent.upload_ratio = 1.509;
+        b.torrent.upload_ratio = 1.501;
+        a.torrent.eta = 630;
+        b.torrent.eta = 600;
+
+        for key in [
+            SortKey::DownloadSpeed,
+            SortKey::UploadSpeed,
+            SortKey::Progress,
+            SortKey::Ratio,
+            SortKey::Eta,
+        ] {
+            assert_eq!(key.compare(&a, &b), Ordering::Equal, "{:?}", key);
+            for ascending in [true, false] {
+                assert_eq!(
+                    compare_torrents(&a, &b, key, ascending, &HashMap::new()),
+                    Ordering::Less,
+                    "{:?}",
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_eta_sorts_last() {
+        let mut a = torrent(1, "a");
+        let mut b = torrent(2, "b");
+        a.torrent.eta = -1;
+        b.torrent.eta = 7 * 24 * 3600;
+        assert_eq!(compare(&a, &b, SortKey::Eta), Ordering::Greater);
+    }
+
+    #[test]
+    fn ties_keep_the_previous_order() {
+        let a = torrent(1, "same");
+        let b = torrent(2, "same");
+        let previous_order =
+            HashMap::from([(b.hash_string.clone(), 0), (a.hash_string.clone(), 1)]);
+
+        for ascending in [true, false] {
+            assert_eq!(
+                compare_torrents(&a, &b, SortKey::Name, ascending, &previous_order),
+                Ordering::Greater
+            );
+        }
+    }
+
+    #[test]
+    fn new_torrents_sort_after_previously_shown_ones() {
+        let a = torrent(1, "same");
+        let b = torrent(2, "same");
+        let previous_order = HashMap::from([(b.hash_string.clone(), 3)]);
+        assert_eq!(
+            compare_torrents(&a, &b, SortKey::Name, true, &previous_order),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn ties_without_a_previous_order_fall_back_to_id() {
+        let a = torrent(2, "same");
+        let b = torrent(1, "same");
+        assert_eq!(compare(&a, &b, SortKey::Name), Ordering::Greater);
+    }
+}
@@ -9,10 +9,13 @@ pub struct Toast {
 #[derive(Debug)]
 pub enum ToastMsg {
     Show(String),
+    ShowWithAction(String, String), // title, action label
 }
 
 #[derive(Debug)]
-pub enum ToastOutput {}
+pub enum ToastOutput {
+    Action,
+}
 
 #[relm4::component(pub)]
 impl SimpleComponent for Toast {
@@ -27,9 +30,14 @@ impl SimpleComponent for Toast {
     fn init(
         _init: Self::Init,
         root: Self::Root,
-        _sender: relm4::ComponentSender<Self>,
+        sender: relm4::ComponentSender<Self>,
     ) -> relm4::ComponentParts<Self> {
         let widgets = view_output!();
+
+        root.connect_default_action(move |_| {
+            sender.output(ToastOutput::Action).ok();
+        });
+
         let model = Self { toast: root };
 
         ComponentParts { model, widgets }
@@ -39,6 +47,12 @@ impl SimpleComponent for Toast {
         match message {
             ToastMsg::Show(title) => {
                 self.toast.set_title(&title);
+                self.toast.set_default_action(None);
+                self.toast.send_notification();
+            }
+            ToastMsg::ShowWithAction(title, action) => {
+                self.toast.set_title(&title);
+                self.toast.set_default_action(Some(&action));
                 self.toast.send_notification();
             }
         }
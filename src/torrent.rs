@@ -1,13 +1,16 @@
 use crate::fl;
+use crate::utils::format_ratio;
 use gtk::gio::functions::content_type_get_icon;
-use gtk::prelude::{ButtonExt, GridExt, WidgetExt};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib, pango};
 use gtk4::gio::content_type_guess;
-use relm4::factory::FactoryComponent;
-use relm4::gtk::gio::Icon;
 use relm4::{gtk, RelmWidgetExt};
+use std::cell::{Cell, Ref, RefCell};
+use std::rc::Rc;
 use transmission_client::TorrentFiles;
 
-fn get_icon_type_for_files(files: &[transmission_client::File]) -> Icon {
+fn get_icon_type_for_files(files: &[transmission_client::File]) -> gio::Icon {
     match files.len() {
         0 => content_type_get_icon("application/x-bittorrent"),
         1 => {
@@ -18,33 +21,15 @@ fn get_icon_type_for_files(files: &[transmission_client::File]) -> Icon {
     }
 }
 
-#[derive(Debug)]
-#[tracker::track]
-pub(crate) struct Torrent {
-    pub hash: String,
-    pub id: i32,
-    pub name: String,
-    pub magnet_link: String,
-    pub percent_done: f32,
-    pub state: TorrentState,
-    pub files: TorrentFiles,
-    pub rate_download: i32,
-    pub rate_upload: i32,
-    pub eta: i64,
-    pub download_dir: String,
-}
-
-#[derive(Debug)]
-pub enum TorrentMsg {
-    Pause,
-    Resume,
-}
-
 #[derive(Debug)]
 pub enum TorrentOutput {
     Pause(String),
     Resume(String),
-    GetFiles(i32),
+    Rename(String, i32, String, String), // hash, id, old name, new name
+    Verify(String),
+    Reannounce(String),
+    SetLocation(String, String), // hash, current download dir
+    QueueDrop(String, String),   // dragged hash, hash it was dropped on
 }
 
 fn get_pause_resume_text(state: &TorrentState) -> String {
@@ -54,26 +39,112 @@ fn get_pause_resume_text(state: &TorrentState) -> String {
     }
 }
 
-fn generate_status_text(
-    state: &TorrentState,
-    rate_download: i32,
-    rate_upload: i32,
-    eta: i64,
-) -> String {
-    match state {
-        TorrentState::Downloading | TorrentState::Seeding => {
-            format!(
-                "\u{2b07}{}/s \u{2b06}{}/s — {} remaining",
-                gtk::glib::format_size(rate_download as u64),
-                gtk::glib::format_size(rate_upload as u64),
-                time_to_string(eta)
+/// The fields of a torrent that feed into its status line
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatusInfo {
+    pub state: TorrentState,
+    pub rate_download: i32,
+    pub rate_upload: i32,
+    pub eta: i64,
+    pub metadata_percent_complete: f32,
+    pub recheck_progress: f32,
+    pub queue_position: i32,
+    pub peers_connected: i32,
+    pub peers_sending_to_us: i32,
+    pub peers_getting_from_us: i32,
+    pub upload_ratio: f32,
+    pub is_finished: bool,
+    pub is_stalled: bool,
+    pub error: i32,
+    pub error_string: String,
+}
+
+impl From<&crate::rpc::Torrent> for StatusInfo {
+    fn from(torrent: &crate::rpc::Torrent) -> Self {
+        Self {
+            state: torrent.status.try_into().unwrap_or(TorrentState::Stopped),
+            rate_download: torrent.rate_download,
+            rate_upload: torrent.rate_upload,
+            eta: torrent.eta,
+            metadata_percent_complete: torrent.metadata_percent_complete,
+            recheck_progress: torrent.recheck_progress,
+            queue_position: torrent.queue_position,
+            peers_connected: torrent.peers_connected,
+            peers_sending_to_us: torrent.peers_sending_to_us,
+            peers_getting_from_us: torrent.peers_getting_from_us,
+            upload_ratio: torrent.upload_ratio,
+            is_finished: torrent.is_finished,
+            is_stalled: torrent.is_stalled,
+            error: torrent.error,
+            error_string: torrent.error_string.clone(),
+        }
+    }
+}
+
+fn percent(fraction: f32) -> String {
+    format!("{:.0}", fraction * 100.0)
+}
+
+fn generate_status_text(status: &StatusInfo) -> String {
+    // The daemon's error codes: 1 is a tracker warning, 2 a tracker error and 3 a local error
+    match status.error {
+        1 => {
+            return fl!(
+                "status-tracker-warning",
+                message = status.error_string.clone()
             )
         }
+        2 => {
+            return fl!(
+                "status-tracker-error",
+                message = status.error_string.clone()
+            )
+        }
+        3 => return fl!("status-local-error", message = status.error_string.clone()),
+        _ => {}
+    }
+
+    let download = gtk::glib::format_size(status.rate_download as u64).to_string();
+    let upload = gtk::glib::format_size(status.rate_upload as u64).to_string();
+
+    match status.state {
+        TorrentState::Stopped if status.is_finished => {
+            fl!("status-finished", ratio = format_ratio(status.upload_ratio))
+        }
         TorrentState::Stopped => fl!("state-paused"),
-        TorrentState::CheckWaiting | TorrentState::DownloadWaiting | TorrentState::SeedWaiting => {
-            fl!("state-waiting")
+        TorrentState::CheckWaiting => fl!("status-verify-queued"),
+        TorrentState::Checking => fl!(
+            "status-verifying",
+            percent = percent(status.recheck_progress)
+        ),
+        TorrentState::DownloadWaiting | TorrentState::SeedWaiting => {
+            fl!("status-queued", position = status.queue_position + 1)
+        }
+        TorrentState::Downloading if status.metadata_percent_complete < 1.0 => fl!(
+            "status-fetching-metadata",
+            percent = percent(status.metadata_percent_complete),
+            peers = status.peers_connected
+        ),
+        TorrentState::Downloading if status.peers_connected == 0 => {
+            fl!("status-stalled-no-peers")
+        }
+        TorrentState::Downloading if status.is_stalled => {
+            fl!("status-stalled", peers = status.peers_connected)
         }
-        TorrentState::Checking => fl!("state-checking"),
+        TorrentState::Downloading => fl!(
+            "status-downloading",
+            sending = status.peers_sending_to_us,
+            peers = status.peers_connected,
+            down = download,
+            up = upload,
+            remaining = time_to_string(status.eta)
+        ),
+        TorrentState::Seeding => fl!(
+            "status-seeding",
+            peers = status.peers_getting_from_us,
+            up = upload,
+            ratio = format_ratio(status.upload_ratio)
+        ),
     }
 }
 
@@ -107,13 +178,14 @@ fn time_to_string(total_seconds: i64) -> String {
 }
 
 impl TorrentState {
-    fn is_stopped(&self) -> bool {
+    pub fn is_stopped(&self) -> bool {
         matches!(self, TorrentState::Stopped)
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub(crate) enum TorrentState {
+    #[default]
     Stopped = 0,
     CheckWaiting = 1,
     Checking = 2,
@@ -140,131 +212,805 @@ impl TryFrom<i32> for TorrentState {
     }
 }
 
-#[relm4::factory(pub)]
-impl FactoryComponent for Torrent {
-    type Init = transmission_client::Torrent;
-    type Input = TorrentMsg;
-    type Output = TorrentOutput;
-    type ParentWidget = gtk::ListBox;
-    type CommandOutput = ();
-
-    view! {
-        gtk::Grid {
-            set_column_spacing: 12,
-            set_row_spacing: 3,
-
-            attach[0, 0, 1, 4] = &gtk::Image {
-                #[track = "self.changed(Torrent::files())"]
-                set_from_gicon: &get_icon_type_for_files(&self.files.files),
-                set_pixel_size: 48,
-            },
-
-            attach[1, 0, 1, 1] = &gtk::Label {
-                #[track = "self.changed(Torrent::name())"]
-                set_text: &self.name,
-                set_ellipsize: gtk::pango::EllipsizeMode::End,
-                set_halign: gtk::Align::Start,
-                add_css_class: granite::STYLE_CLASS_H3_LABEL,
-            },
-
-            attach[1, 1, 1, 1] = &gtk::Label {
-                #[track = "self.changed(Torrent::state() | Torrent::rate_download() | Torrent::rate_upload() | Torrent::eta())"]
-                set_text: &generate_status_text(&self.state, self.rate_download, self.rate_upload, self.eta),
-                set_halign: gtk::Align::Start,
-                add_css_class: granite::STYLE_CLASS_SMALL_LABEL,
-            },
-
-            attach[1, 2, 1, 1] = &gtk::ProgressBar {
-                #[track = "self.changed(Torrent::percent_done())"]
-                set_fraction: self.percent_done.into(),
-                #[track = "self.changed(Torrent::state())"]
-                set_class_active: ("seeding", self.state == TorrentState::Seeding),
-                set_hexpand: true,
-            },
-
-            attach[2, 1, 1, 4] = if self.state.is_stopped() {
-                &gtk::Button {
-                    set_icon_name: "media-playback-start-symbolic",
-                    set_tooltip_text: Some(&get_pause_resume_text(&self.state)),
-                    add_css_class: granite::STYLE_CLASS_ROUNDED,
-                    connect_clicked => TorrentMsg::Resume,
-                }
-            } else {
-                &gtk::Button {
-                    set_icon_name: "media-playback-pause-symbolic",
-                    set_tooltip_text: Some(&get_pause_resume_text(&self.state)),
-                    add_css_class: granite::STYLE_CLASS_ROUNDED,
-                    connect_clicked => TorrentMsg::Pause,
-                }
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct TorrentObject {
+        pub data: RefCell<Option<crate::rpc::Torrent>>,
+        pub files: RefCell<TorrentFiles>,
+        pub tracker_domains: RefCell<Vec<String>>,
+        pub status_text: RefCell<String>,
+        pub move_target: RefCell<Option<String>>,
+        pub renaming: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TorrentObject {
+        const NAME: &'static str = "TorrentialTorrentObject";
+        type Type = super::TorrentObject;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for TorrentObject {
+        fn properties() -> &'static [glib::ParamSpec] {
+            use std::sync::OnceLock;
+            static PROPERTIES: OnceLock<Vec<glib::ParamSpec>> = OnceLock::new();
+            PROPERTIES.get_or_init(|| {
+                vec![
+                    glib::ParamSpecString::builder("name").read_only().build(),
+                    glib::ParamSpecString::builder("status-text")
+                        .read_only()
+                        .build(),
+                    glib::ParamSpecInt::builder("state").read_only().build(),
+                    glib::ParamSpecDouble::builder("progress")
+                        .read_only()
+                        .build(),
+                    glib::ParamSpecInt::builder("error").read_only().build(),
+                    glib::ParamSpecInt::builder("queue-position")
+                        .read_only()
+                        .build(),
+                    glib::ParamSpecObject::builder::<gio::Icon>("icon")
+                        .read_only()
+                        .build(),
+                    glib::ParamSpecString::builder("move-target")
+                        .read_only()
+                        .build(),
+                    glib::ParamSpecBoolean::builder("renaming")
+                        .read_only()
+                        .build(),
+                ]
+            })
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            let obj = self.obj();
+            match pspec.name() {
+                "name" => obj.name().to_value(),
+                "status-text" => obj.status_text().to_value(),
+                "state" => (obj.state() as i32).to_value(),
+                "progress" => obj.progress().to_value(),
+                "error" => obj.error().to_value(),
+                "queue-position" => obj.queue_position().to_value(),
+                "icon" => obj.icon().to_value(),
+                "move-target" => obj.move_target().to_value(),
+                "renaming" => obj.renaming().to_value(),
+                _ => unimplemented!(),
             }
         }
     }
 
-    fn init_model(
-        init: Self::Init,
-        _index: &Self::Index,
-        sender: relm4::prelude::FactorySender<Self>,
-    ) -> Self {
-        sender.output(TorrentOutput::GetFiles(init.id)).unwrap();
+    #[derive(Debug, Default)]
+    pub struct TorrentRow {
+        pub icon: gtk::Image,
+        pub name_stack: gtk::Stack,
+        pub name_label: gtk::Label,
+        pub name_entry: gtk::Entry,
+        pub error_icon: gtk::Image,
+        pub spinner: gtk::Spinner,
+        pub status_label: gtk::Label,
+        pub recovery_button: gtk::MenuButton,
+        pub set_location_button: gtk::Button,
+        pub verify_button: gtk::Button,
+        pub reannounce_button: gtk::Button,
+        pub queue_label: gtk::Label,
+        pub progress_bar: gtk::ProgressBar,
+        pub pause_resume_button: gtk::Button,
+        pub torrent: RefCell<Option<super::TorrentObject>>,
+        pub notify_handler: RefCell<Option<glib::SignalHandlerId>>,
+    }
 
-        Self {
-            hash: init.hash_string,
-            id: init.id,
-            name: init.name,
-            magnet_link: init.magnet_link,
-            percent_done: init.percent_done,
-            state: TorrentState::Stopped,
-            tracker: Default::default(),
-            files: Default::default(),
-            rate_download: init.rate_download,
-            rate_upload: init.rate_upload,
-            eta: init.eta,
-            download_dir: init.download_dir,
+    #[glib::object_subclass]
+    impl ObjectSubclass for TorrentRow {
+        const NAME: &'static str = "TorrentialTorrentRow";
+        type Type = super::TorrentRow;
+        type ParentType = gtk::Grid;
+    }
+
+    impl ObjectImpl for TorrentRow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().build();
+        }
+    }
+
+    impl WidgetImpl for TorrentRow {}
+
+    impl GridImpl for TorrentRow {}
+}
+
+glib::wrapper! {
+    pub struct TorrentObject(ObjectSubclass<imp::TorrentObject>);
+}
+
+impl TorrentObject {
+    pub fn new(torrent: crate::rpc::Torrent) -> Self {
+        let obj: Self = glib::Object::new();
+        let imp = obj.imp();
+        imp.status_text
+            .replace(generate_status_text(&StatusInfo::from(&torrent)));
+        imp.data.replace(Some(torrent));
+        obj
+    }
+
+    /// The daemon's latest snapshot of this torrent
+    pub fn data(&self) -> Ref<'_, crate::rpc::Torrent> {
+        Ref::map(self.imp().data.borrow(), |data| {
+            data.as_ref()
+                .expect("TorrentObject is always created with data")
+        })
+    }
+
+    pub fn hash(&self) -> String {
+        self.data().hash_string.clone()
+    }
+
+    pub fn id(&self) -> i32 {
+        self.data().id
+    }
+
+    pub fn name(&self) -> String {
+        self.data().name.clone()
+    }
+
+    pub fn magnet_link(&self) -> String {
+        self.data().magnet_link.clone()
+    }
+
+    pub fn download_dir(&self) -> String {
+        self.data().download_dir.clone()
+    }
+
+    pub fn torrent_file(&self) -> String {
+        self.data().torrent_file.clone()
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        self.data().labels.clone()
+    }
+
+    pub fn state(&self) -> TorrentState {
+        self.data()
+            .status
+            .try_into()
+            .unwrap_or(TorrentState::Stopped)
+    }
+
+    pub fn error(&self) -> i32 {
+        self.data().error
+    }
+
+    pub fn error_string(&self) -> String {
+        self.data().error_string.clone()
+    }
+
+    pub fn queue_position(&self) -> i32 {
+        self.data().queue_position
+    }
+
+    pub fn size_on_disk(&self) -> i64 {
+        let data = self.data();
+        data.have_valid + data.have_unchecked
+    }
+
+    /// How much of the torrent is downloaded, or how much has been checked while verifying
+    pub fn progress(&self) -> f64 {
+        if self.state() == TorrentState::Checking {
+            self.data().recheck_progress.into()
+        } else {
+            self.data().percent_done.into()
+        }
+    }
+
+    pub fn status_text(&self) -> String {
+        self.imp().status_text.borrow().clone()
+    }
+
+    pub fn files(&self) -> TorrentFiles {
+        self.imp().files.borrow().clone()
+    }
+
+    pub fn file_count(&self) -> i32 {
+        self.imp().files.borrow().file_count
+    }
+
+    /// Whether any file's path contains `text`, which should already be lowercase
+    pub fn has_file_matching(&self, text: &str) -> bool {
+        self.imp()
+            .files
+            .borrow()
+            .files
+            .iter()
+            .any(|file| file.name.to_lowercase().contains(text))
+    }
+
+    pub fn set_files(&self, files: TorrentFiles) {
+        self.imp().files.replace(files);
+        self.notify("icon");
+    }
+
+    pub fn icon(&self) -> gio::Icon {
+        get_icon_type_for_files(&self.imp().files.borrow().files)
+    }
+
+    /// The domains of this torrent's trackers, without duplicates
+    pub fn tracker_domains(&self) -> Vec<String> {
+        self.imp().tracker_domains.borrow().clone()
+    }
+
+    pub fn set_tracker_domains(&self, domains: Vec<String>) {
+        self.imp().tracker_domains.replace(domains);
+    }
+
+    pub fn move_target(&self) -> Option<String> {
+        self.imp().move_target.borrow().clone()
+    }
+
+    /// Show the torrent as moving until the daemon reports it at `location`
+    pub fn start_move(&self, location: String) {
+        self.imp().move_target.replace(Some(location));
+        self.notify("move-target");
+    }
+
+    pub fn renaming(&self) -> bool {
+        self.imp().renaming.get()
+    }
+
+    pub fn set_renaming(&self, renaming: bool) {
+        if self.imp().renaming.replace(renaming) != renaming {
+            self.notify("renaming");
         }
     }
 
-    fn update(&mut self, msg: Self::Input, sender: relm4::prelude::FactorySender<Self>) {
-        self.reset();
-        match msg {
-            TorrentMsg::Pause => sender
-                .output(TorrentOutput::Pause(self.hash.clone()))
-                .unwrap(),
-            TorrentMsg::Resume => sender
-                .output(TorrentOutput::Resume(self.hash.clone()))
-                .unwrap(),
+    /// Replace the snapshot with a newer one from the daemon, notifying only the properties
+    /// that changed so bound rows don't redraw every refresh
+    pub fn update(&self, torrent: crate::rpc::Torrent) {
+        let imp = self.imp();
+        let mut changed = vec![];
+
+        if let Some(old) = imp.data.borrow().as_ref() {
+            if old.name != torrent.name {
+                changed.push("name");
+            }
+            if old.status != torrent.status {
+                changed.push("state");
+            }
+            if old.percent_done != torrent.percent_done
+                || old.recheck_progress != torrent.recheck_progress
+            {
+                changed.push("progress");
+            }
+            if old.error != torrent.error || old.error_string != torrent.error_string {
+                changed.push("error");
+            }
+            if old.queue_position != torrent.queue_position {
+                changed.push("queue-position");
+            }
+        }
+
+        let status_text = generate_status_text(&StatusInfo::from(&torrent));
+        if *imp.status_text.borrow() != status_text {
+            imp.status_text.replace(status_text);
+            changed.push("status-text");
+        }
+
+        // The daemon only reports the new location once the data has finished moving
+        let move_finished = imp
+            .move_target
+            .borrow()
+            .as_ref()
+            .is_some_and(|target| *target == torrent.download_dir || torrent.error != 0);
+        if move_finished {
+            imp.move_target.replace(None);
+            changed.push("move-target");
+        }
+
+        imp.data.replace(Some(torrent));
+        for property in changed {
+            self.notify(property);
         }
     }
 }
 
-impl Torrent {
-    pub fn update(&mut self, torrent: &transmission_client::Torrent) {
-        self.reset();
+glib::wrapper! {
+    pub struct TorrentRow(ObjectSubclass<imp::TorrentRow>)
+        @extends gtk::Grid, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+/// The torrent shown by the row that `widget` is in
+fn row_torrent(widget: &impl IsA<gtk::Widget>) -> Option<TorrentObject> {
+    widget
+        .ancestor(TorrentRow::static_type())
+        .and_downcast::<TorrentRow>()
+        .and_then(|row| row.torrent())
+}
+
+impl TorrentRow {
+    fn new(output: Rc<dyn Fn(TorrentOutput)>) -> Self {
+        let row: Self = glib::Object::new();
+        row.connect_output(output);
+        row
+    }
+
+    pub fn torrent(&self) -> Option<TorrentObject> {
+        self.imp().torrent.borrow().clone()
+    }
+
+    fn build(&self) {
+        let imp = self.imp();
+        self.set_column_spacing(12);
+        self.set_row_spacing(3);
+        self.set_margin_top(6);
+        self.set_margin_bottom(6);
+        self.set_margin_start(6);
+        self.set_margin_end(6);
+
+        imp.icon.set_pixel_size(48);
+        self.attach(&imp.icon, 0, 0, 1, 4);
+
+        imp.name_label.set_ellipsize(pango::EllipsizeMode::End);
+        imp.name_label.set_halign(gtk::Align::Start);
+        imp.name_label.add_css_class(granite::STYLE_CLASS_H3_LABEL);
+        imp.name_stack.add_named(&imp.name_label, Some("label"));
+        imp.name_stack.add_named(&imp.name_entry, Some("entry"));
+        self.attach(&imp.name_stack, 1, 0, 1, 1);
+
+        let status_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        imp.error_icon.set_icon_name(Some("dialog-error-symbolic"));
+        status_box.append(&imp.error_icon);
+        status_box.append(&imp.spinner);
+
+        imp.status_label.set_halign(gtk::Align::Start);
+        imp.status_label.set_ellipsize(pango::EllipsizeMode::End);
+        imp.status_label
+            .add_css_class(granite::STYLE_CLASS_SMALL_LABEL);
+        status_box.append(&imp.status_label);
+
+        // Missing or unreadable data is a local error, and is usually fixed by pointing the
+        // torrent at wherever the data now lives
+        imp.set_location_button
+            .set_label(&fl!("action-set-location"));
+        imp.verify_button.set_label(&fl!("action-verify"));
+        imp.reannounce_button.set_label(&fl!("action-reannounce"));
+
+        let recovery_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        for button in [
+            &imp.set_location_button,
+            &imp.verify_button,
+            &imp.reannounce_button,
+        ] {
+            button.add_css_class("flat");
+            recovery_box.append(button);
+        }
+
+        let recovery_popover = gtk::Popover::new();
+        recovery_popover.set_child(Some(&recovery_box));
+        imp.recovery_button.set_popover(Some(&recovery_popover));
+        imp.recovery_button.set_icon_name("view-more-symbolic");
+        imp.recovery_button
+            .set_tooltip_text(Some(&fl!("torrent-recovery-tooltip")));
+        imp.recovery_button.set_valign(gtk::Align::Center);
+        imp.recovery_button.add_css_class("flat");
+        status_box.append(&imp.recovery_button);
+        self.attach(&status_box, 1, 1, 1, 1);
+
+        imp.queue_label
+            .set_tooltip_text(Some(&fl!("queue-position-tooltip")));
+        imp.queue_label.set_halign(gtk::Align::End);
+        imp.queue_label
+            .add_css_class(granite::STYLE_CLASS_SMALL_LABEL);
+        imp.queue_label.add_css_class("dim-label");
+        self.attach(&imp.queue_label, 2, 0, 1, 1);
+
+        imp.progress_bar.set_hexpand(true);
+        self.attach(&imp.progress_bar, 1, 2, 1, 1);
+
+        imp.pause_resume_button
+            .add_css_class(granite::STYLE_CLASS_ROUNDED);
+        self.attach(&imp.pause_resume_button, 2, 1, 1, 4);
+    }
+
+    /// Connect the row's buttons, entry and drag and drop, which act on whichever torrent the
+    /// row is showing at the time
+    fn connect_output(&self, output: Rc<dyn Fn(TorrentOutput)>) {
+        let imp = self.imp();
+
+        let pause_output = output.clone();
+        imp.pause_resume_button.connect_clicked(move |button| {
+            if let Some(torrent) = row_torrent(button) {
+                pause_output(if torrent.state().is_stopped() {
+                    TorrentOutput::Resume(torrent.hash())
+                } else {
+                    TorrentOutput::Pause(torrent.hash())
+                });
+            }
+        });
+
+        let rename_output = output.clone();
+        imp.name_entry.connect_activate(move |entry| {
+            if let Some(torrent) = row_torrent(entry) {
+                torrent.set_renaming(false);
+
+                let new_name = entry.text().to_string();
+                if new_name != torrent.name() {
+                    rename_output(TorrentOutput::Rename(
+                        torrent.hash(),
+                        torrent.id(),
+                        torrent.name(),
+                        new_name,
+                    ));
+                }
+            }
+        });
+
+        let escape_controller = gtk::EventControllerKey::new();
+        escape_controller.connect_key_pressed(|controller, key, _, _| {
+            if key != gdk::Key::Escape {
+                return glib::Propagation::Proceed;
+            }
+
+            if let Some(torrent) = controller.widget().and_then(|widget| row_torrent(&widget)) {
+                torrent.set_renaming(false);
+            }
+            glib::Propagation::Stop
+        });
+        imp.name_entry.add_controller(escape_controller);
+
+        // Clicking away, e.g. on another row, leaves the name as it was, like Escape
+        let focus_controller = gtk::EventControllerFocus::new();
+        focus_controller.connect_leave(|controller| {
+            if let Some(torrent) = controller.widget().and_then(|widget| row_torrent(&widget)) {
+                torrent.set_renaming(false);
+            }
+        });
+        imp.name_entry.add_controller(focus_controller);
+
+        let recovery_actions: [(&gtk::Button, fn(&TorrentObject) -> TorrentOutput); 3] = [
+            (&imp.set_location_button, |torrent| {
+                TorrentOutput::SetLocation(torrent.hash(), torrent.download_dir())
+            }),
+            (&imp.verify_button, |torrent| {
+                TorrentOutput::Verify(torrent.hash())
+            }),
+            (&imp.reannounce_button, |torrent| {
+                TorrentOutput::Reannounce(torrent.hash())
+            }),
+        ];
+        for (button, action) in recovery_actions {
+            let output = output.clone();
+            button.connect_clicked(move |button| {
+                if let Some(popover) = button
+                    .ancestor(gtk::Popover::static_type())
+                    .and_downcast::<gtk::Popover>()
+                {
+                    popover.popdown();
+                }
+
+                if let Some(torrent) = row_torrent(button) {
+                    output(action(&torrent));
+                }
+            });
+        }
+
+        // Dragging one row onto another moves it to that row's place in the queue
+        let drag_source = gtk::DragSource::new();
+        drag_source.set_actions(gdk::DragAction::MOVE);
+        drag_source.connect_prepare(|source, _, _| {
+            let torrent = source.widget().and_then(|widget| row_torrent(&widget))?;
+            Some(gdk::ContentProvider::for_value(&torrent.hash().to_value()))
+        });
+        self.add_controller(drag_source);
+
+        let drop_target = gtk::DropTarget::new(glib::Type::STRING, gdk::DragAction::MOVE);
+        drop_target.connect_drop(move |target, value, _, _| {
+            let (Ok(dragged_hash), Some(torrent)) = (
+                value.get::<String>(),
+                target.widget().and_then(|widget| row_torrent(&widget)),
+            ) else {
+                return false;
+            };
+
+            if dragged_hash != torrent.hash() {
+                output(TorrentOutput::QueueDrop(dragged_hash, torrent.hash()));
+            }
+            true
+        });
+        self.add_controller(drop_target);
+    }
+
+    fn bind(&self, torrent: &TorrentObject) {
+        let row = self.downgrade();
+        let handler = torrent.connect_notify_local(None, move |torrent, pspec| {
+            if let Some(row) = row.upgrade() {
+                row.refresh(torrent, Some(pspec.name()));
+            }
+        });
+
+        let imp = self.imp();
+        imp.torrent.replace(Some(torrent.clone()));
+        imp.notify_handler.replace(Some(handler));
+        self.refresh(torrent, None);
+    }
+
+    fn unbind(&self) {
+        let imp = self.imp();
+        if let (Some(torrent), Some(handler)) = (imp.torrent.take(), imp.notify_handler.take()) {
+            torrent.disconnect(handler);
+        }
+    }
+
+    /// Update the widgets that show `property`, or every widget if it's `None`
+    fn refresh(&self, torrent: &TorrentObject, property: Option<&str>) {
+        let imp = self.imp();
+        let changed = |name: &str| property.is_none() || property == Some(name);
 
-        if self.hash != torrent.hash_string {
-            self.hash.clone_from(&torrent.hash_string);
+        if changed("icon") {
+            imp.icon.set_from_gicon(&torrent.icon());
         }
 
-        if self.id != torrent.id {
-            self.id = torrent.id;
+        if changed("name") {
+            imp.name_label.set_text(&torrent.name());
         }
 
-        if self.name != torrent.name {
-            self.set_name(torrent.name.clone());
+        if changed("renaming") {
+            if torrent.renaming() {
+                imp.name_entry.set_text(&torrent.name());
+                imp.name_stack.set_visible_child_name("entry");
+                imp.name_entry.grab_focus();
+            } else {
+                imp.name_stack.set_visible_child_name("label");
+            }
         }
 
-        if self.magnet_link != torrent.magnet_link {
-            self.set_magnet_link(torrent.magnet_link.clone());
+        if changed("error") {
+            let error = torrent.error();
+            let error_string = torrent.error_string();
+            imp.error_icon.set_visible(error != 0);
+            imp.status_label.set_class_active("error", error != 0);
+            imp.status_label
+                .set_tooltip_text(if error_string.is_empty() {
+                    None
+                } else {
+                    Some(&error_string)
+                });
+            imp.recovery_button.set_visible(error != 0);
+            imp.set_location_button.set_visible(error == 3);
+            imp.reannounce_button.set_visible(error == 1 || error == 2);
         }
 
-        self.set_state(torrent.status.try_into().unwrap_or(TorrentState::Stopped));
-        self.set_percent_done(torrent.percent_done);
+        if changed("status-text") || changed("move-target") {
+            let move_target = torrent.move_target();
+            imp.spinner.set_visible(move_target.is_some());
+            imp.spinner.set_spinning(move_target.is_some());
+            imp.status_label.set_text(&match move_target {
+                Some(location) => fl!("status-moving", location = location),
+                None => torrent.status_text(),
+            });
+        }
 
-        self.set_rate_download(torrent.rate_download);
-        self.set_rate_upload(torrent.rate_upload);
-        self.set_eta(torrent.eta);
+        if changed("queue-position") {
+            imp.queue_label.set_label(&fl!(
+                "queue-position",
+                position = torrent.queue_position() + 1
+            ));
+        }
+
+        if changed("progress") || changed("state") {
+            imp.progress_bar.set_fraction(torrent.progress());
+        }
 
-        if self.download_dir != torrent.download_dir {
-            self.set_download_dir(torrent.download_dir.clone());
+        if changed("state") {
+            let state = torrent.state();
+            imp.progress_bar
+                .set_class_active("seeding", state == TorrentState::Seeding);
+            imp.progress_bar
+                .set_class_active("verifying", state == TorrentState::Checking);
+            imp.pause_resume_button
+                .set_icon_name(if state.is_stopped() {
+                    "media-playback-start-symbolic"
+                } else {
+                    "media-playback-pause-symbolic"
+                });
+            imp.pause_resume_button
+                .set_tooltip_text(Some(&get_pause_resume_text(&state)));
         }
     }
 }
+
+/// Build the rows of the torrent list, which pass their buttons' actions on to `output`
+pub fn torrent_factory(output: impl Fn(TorrentOutput) + 'static) -> gtk::SignalListItemFactory {
+    let output: Rc<dyn Fn(TorrentOutput)> = Rc::new(output);
+    let factory = gtk::SignalListItemFactory::new();
+
+    factory.connect_setup(move |_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<gtk::ListItem>()
+            .expect("Needs to be ListItem");
+
+        list_item.set_child(Some(&TorrentRow::new(output.clone())));
+    });
+
+    factory.connect_bind(|_, list_item| {
+        let (row, torrent) = bound_row(list_item);
+        row.bind(&torrent);
+    });
+
+    factory.connect_unbind(|_, list_item| {
+        let (row, _) = bound_row(list_item);
+        row.unbind();
+    });
+
+    factory
+}
+
+/// Get the row a ListItem shows and the torrent it's currently bound to
+fn bound_row(list_item: &glib::Object) -> (TorrentRow, TorrentObject) {
+    let list_item = list_item
+        .downcast_ref::<gtk::ListItem>()
+        .expect("Needs to be ListItem");
+
+    let row = list_item
+        .child()
+        .and_downcast::<TorrentRow>()
+        .expect("Needs to be TorrentRow");
+
+    let torrent = list_item
+        .item()
+        .and_downcast::<TorrentObject>()
+        .expect("Needs to be TorrentObject");
+
+    (row, torrent)
+}
+
+/// The torrent in the row under a point in the list's coordinates
+pub fn torrent_at(list_view: &gtk::ListView, x: f64, y: f64) -> Option<TorrentObject> {
+    list_view
+        .pick(x, y, gtk::PickFlags::DEFAULT)
+        .and_then(|widget| row_torrent(&widget))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i18n_embed::LanguageLoader;
+
+    fn status_text(status: StatusInfo) -> String {
+        crate::STATIC_LANGUAGE_LOADER
+            .load_fallback_language(&crate::Localizations)
+            .unwrap();
+        crate::STATIC_LANGUAGE_LOADER.set_use_isolating(false);
+
+        generate_status_text(&status)
+    }
+
+    fn downloading() -> StatusInfo {
+        StatusInfo {
+            state: TorrentState::Downloading,
+            metadata_percent_complete: 1.0,
+            peers_connected: 12,
+            peers_sending_to_us: 5,
+            eta: 90,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn paused() {
+        assert_eq!(status_text(StatusInfo::default()), "Paused");
+    }
+
+    #[test]
+    fn finished_shows_ratio() {
+        let text = status_text(StatusInfo {
+            is_finished: true,
+            upload_ratio: 2.0,
+            ..Default::default()
+        });
+        assert!(text.starts_with("Finished"), "{}", text);
+        assert!(text.contains("2.00"), "{}", text);
+    }
+
+    #[test]
+    fn fetching_metadata() {
+        let text = status_text(StatusInfo {
+            metadata_percent_complete: 0.25,
+            ..downloading()
+        });
+        assert!(text.contains("metadata"), "{}", text);
+        assert!(text.contains("25%"), "{}", text);
+    }
+
+    #[test]
+    fn verifying_shows_percent() {
+        let text = status_text(StatusInfo {
+            state: TorrentState::Checking,
+            recheck_progress: 0.5,
+            ..Default::default()
+        });
+        assert!(text.contains("50%"), "{}", text);
+    }
+
+    #[test]
+    fn waiting_to_verify() {
+        let text = status_text(StatusInfo {
+            state: TorrentState::CheckWaiting,
+            ..Default::default()
+        });
+        assert!(text.contains("verify"), "{}", text);
+    }
+
+    #[test]
+    fn queued_shows_one_based_position() {
+        let text = status_text(StatusInfo {
+            state: TorrentState::DownloadWaiting,
+            queue_position: 2,
+            ..Default::default()
+        });
+        assert!(text.contains('3'), "{}", text);
+    }
+
+    #[test]
+    fn stalled_without_peers() {
+        let text = status_text(StatusInfo {
+            peers_connected: 0,
+            ..downloading()
+        });
+        assert!(text.starts_with("Stalled"), "{}", text);
+    }
+
+    #[test]
+    fn stalled_with_peers() {
+        let text = status_text(StatusInfo {
+            is_stalled: true,
+            ..downloading()
+        });
+        assert!(text.starts_with("Stalled"), "{}", text);
+        assert!(text.contains("12"), "{}", text);
+    }
+
+    #[test]
+    fn downloading_shows_peers_and_time_remaining() {
+        let text = status_text(downloading());
+        assert!(text.contains("5 of 12 peers"), "{}", text);
+        assert!(text.contains("remaining"), "{}", text);
+    }
+
+    #[test]
+    fn seeding_shows_ratio_and_peers_without_time_remaining() {
+        let text = status_text(StatusInfo {
+            state: TorrentState::Seeding,
+            peers_getting_from_us: 3,
+            upload_ratio: 1.5,
+            ..Default::default()
+        });
+        assert!(text.contains("3 peers"), "{}", text);
+        assert!(text.contains("1.50"), "{}", text);
+        assert!(!text.contains("remaining"), "{}", text);
+    }
+
+    #[test]
+    fn errors_take_priority_over_state() {
+        let tracker_warning = status_text(StatusInfo {
+            error: 1,
+            error_string: "Unregistered torrent".to_string(),
+            ..downloading()
+        });
+        assert!(tracker_warning.contains("Unregistered torrent"));
+
+        let tracker_error = status_text(StatusInfo {
+            error: 2,
+            error_string: "Connection refused".to_string(),
+            ..downloading()
+        });
+        assert!(tracker_error.contains("Connection refused"));
+        assert_ne!(tracker_warning, tracker_error);
+
+        let local_error = status_text(StatusInfo {
+            error: 3,
+            error_string: "No space left on device".to_string(),
+            ..Default::default()
+        });
+        assert!(local_error.contains("No space left on device"));
+    }
+}
@@ -1,16 +1,22 @@
 use std::{process::Stdio, time::Duration};
 
+use crate::file_select_dialog::FileSelection;
+use crate::rpc::{
+    non_empty, RpcClient, Torrent, TorrentAddArgs, TorrentDetails, TorrentPeers, TorrentPieces,
+    TorrentSetArgs, TorrentTrackers,
+};
 use nix::{sys::signal, unistd::Pid};
 use relm4::{
     component::{AsyncComponent, AsyncComponentParts},
     gtk::{gio, prelude::SettingsExt},
 };
 use transmission_client::{
-    Client, Encryption, SessionMutator, Torrent, TorrentFiles, TorrentMutator,
+    Client, ClientError, Encryption, SessionMutator, TorrentFiles, TorrentMutator,
 };
 
 pub(crate) struct Transmission {
     tr_client: Option<Client>,
+    rpc_client: RpcClient,
     transmission_process: Option<std::process::Child>,
     timer_handle: Option<tokio::task::JoinHandle<()>>,
 }
@@ -19,19 +25,52 @@ pub(crate) struct Transmission {
 pub(crate) enum TransmissionOutput {
     ConnectionError(String),
     TorrentsChanged(Vec<Torrent>),
+    TorrentAdded(String), // hash of a torrent added through the app, rather than from elsewhere
     FileListChanged(TorrentFiles),
+    DetailsFilesChanged(TorrentFiles),
+    TorrentDetailsChanged(Box<TorrentDetails>),
+    TrackersChanged(TorrentTrackers),
+    AllTrackersChanged(Vec<TorrentTrackers>),
+    PeersChanged(TorrentPeers),
+    PiecesChanged(TorrentPieces),
+    TrackerEditFailed(String),
+    RenamePathFailed(i32, String), // torrent_id, error
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum QueueMove {
+    Top,
+    Up,
+    Down,
+    Bottom,
 }
 
 #[derive(Debug)]
 pub(crate) enum TransmissionInput {
-    AddTorrentFile(String),
+    AddTorrentFile(String, Vec<String>, Option<String>), // path or magnet link, labels, download dir
+    AddTorrentMetainfo(String, Vec<String>, Option<String>), // base64 .torrent file, labels, download dir
     UpdateTorrents,
     PauseTorrents(Vec<String>),
     ResumeTorrents(Vec<String>),
+    StartTorrentsNow(Vec<String>),
+    MoveInQueue(Vec<String>, QueueMove),
+    SetQueuePosition(String, i32), // torrent_hash, queue_position
     GetFiles(i32),
-    SetFilesWanted(String, i32, Vec<i32>, Vec<i32>),
+    GetDetailsFiles(i32),
+    GetTorrentDetails(i32),
+    GetTrackers(i32),
+    GetAllTrackers,
+    GetPeers(i32),
+    GetPieces(i32),
+    SetFileSelection(String, i32, FileSelection),
     UpdateSettings,
-    RemoveTorrents(Vec<String>),
+    RemoveTorrents(Vec<String>, bool), // hashes, delete local data
+    RenamePath(String, i32, String, String), // torrent_hash, torrent_id, path, new_name
+    SetTrackerList(String, i32, String), // torrent_hash, torrent_id, tracker_list
+    ReannounceTorrents(Vec<String>),
+    VerifyTorrents(Vec<String>),
+    SetLocation(Vec<String>, String, bool), // hashes, location, move data
+    SetLabels(String, Vec<String>),
 }
 
 impl Drop for Transmission {
@@ -81,6 +120,7 @@ impl AsyncComponent for Transmission {
                 return AsyncComponentParts {
                     model: Self {
                         tr_client: Some(tr_client),
+                        rpc_client: RpcClient::default(),
                         transmission_process: None,
                         timer_handle: None,
                     },
@@ -163,6 +203,7 @@ impl AsyncComponent for Transmission {
         AsyncComponentParts {
             model: Self {
                 tr_client: Some(tr_client),
+                rpc_client: RpcClient::default(),
                 transmission_process: transmission_daemon,
                 timer_handle: Some(timer_handle),
             },
@@ -184,9 +225,61 @@ impl AsyncComponent for Transmission {
         _root: &Self::Root,
     ) {
         match message {
-            TransmissionInput::AddTorrentFile(path) => {
+            TransmissionInput::AddTorrentFile(path, labels, download_dir) => {
+                let args = TorrentAddArgs {
+                    filename: Some(path),
+                    download_dir,
+                    labels: non_empty(labels),
+                    ..Default::default()
+                };
+                match self.rpc_client.torrent_add(args).await {
+                    Ok(Some(torrent)) => sender
+                        .output(TransmissionOutput::TorrentAdded(torrent.hash_string))
+                        .unwrap(),
+                    Ok(None) => {}
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+                sender.input(TransmissionInput::UpdateTorrents);
+            }
+            TransmissionInput::AddTorrentMetainfo(metainfo, labels, download_dir) => {
+                let args = TorrentAddArgs {
+                    metainfo: Some(metainfo),
+                    download_dir,
+                    labels: non_empty(labels),
+                    ..Default::default()
+                };
+                match self.rpc_client.torrent_add(args).await {
+                    Ok(Some(torrent)) => sender
+                        .output(TransmissionOutput::TorrentAdded(torrent.hash_string))
+                        .unwrap(),
+                    Ok(None) => {}
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+                sender.input(TransmissionInput::UpdateTorrents);
+            }
+            TransmissionInput::UpdateTorrents => match self.rpc_client.torrents(None).await {
+                Ok(torrents) => {
+                    sender
+                        .output(TransmissionOutput::TorrentsChanged(torrents))
+                        .unwrap();
+                }
+                Err(err) => {
+                    sender
+                        .output(TransmissionOutput::ConnectionError(err.to_string()))
+                        .unwrap();
+                }
+            },
+            TransmissionInput::PauseTorrents(hashes) => {
                 let tr_client = self.tr_client.as_ref().unwrap();
-                match tr_client.torrent_add_filename(&path).await {
+                match tr_client.torrent_stop(Some(hashes)).await {
                     Ok(_) => {}
                     Err(err) => {
                         sender
@@ -196,25 +289,42 @@ impl AsyncComponent for Transmission {
                 }
                 sender.input(TransmissionInput::UpdateTorrents);
             }
-            TransmissionInput::UpdateTorrents => {
+            TransmissionInput::ResumeTorrents(hashes) => {
                 let tr_client = self.tr_client.as_ref().unwrap();
-
-                match tr_client.torrents(None).await {
-                    Ok(torrents) => {
+                match tr_client.torrent_start(Some(hashes), false).await {
+                    Ok(_) => {}
+                    Err(err) => {
                         sender
-                            .output(TransmissionOutput::TorrentsChanged(torrents))
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
                             .unwrap();
                     }
+                }
+                sender.input(TransmissionInput::UpdateTorrents);
+            }
+            TransmissionInput::StartTorrentsNow(hashes) => {
+                let tr_client = self.tr_client.as_ref().unwrap();
+                // Bypass the download queue
+                match tr_client.torrent_start(Some(hashes), true).await {
+                    Ok(_) => {}
                     Err(err) => {
                         sender
                             .output(TransmissionOutput::ConnectionError(err.to_string()))
                             .unwrap();
                     }
                 }
+                sender.input(TransmissionInput::UpdateTorrents);
             }
-            TransmissionInput::PauseTorrents(hashes) => {
+            TransmissionInput::MoveInQueue(hashes, queue_move) => {
                 let tr_client = self.tr_client.as_ref().unwrap();
-                match tr_client.torrent_stop(Some(hashes)).await {
+                let ids = Some(hashes);
+                let result = match queue_move {
+                    QueueMove::Top => tr_client.queue_move_top(ids).await,
+                    QueueMove::Up => tr_client.queue_move_up(ids).await,
+                    QueueMove::Down => tr_client.queue_move_down(ids).await,
+                    QueueMove::Bottom => tr_client.queue_move_bottom(ids).await,
+                };
+
+                match result {
                     Ok(_) => {}
                     Err(err) => {
                         sender
@@ -224,9 +334,15 @@ impl AsyncComponent for Transmission {
                 }
                 sender.input(TransmissionInput::UpdateTorrents);
             }
-            TransmissionInput::ResumeTorrents(hashes) => {
+            TransmissionInput::SetQueuePosition(hash, queue_position) => {
                 let tr_client = self.tr_client.as_ref().unwrap();
-                match tr_client.torrent_start(Some(hashes), false).await {
+
+                let mutator = TorrentMutator {
+                    queue_position: Some(queue_position),
+                    ..Default::default()
+                };
+
+                match tr_client.torrent_set(Some(vec![hash]), mutator).await {
                     Ok(_) => {}
                     Err(err) => {
                         sender
@@ -236,9 +352,12 @@ impl AsyncComponent for Transmission {
                 }
                 sender.input(TransmissionInput::UpdateTorrents);
             }
-            TransmissionInput::RemoveTorrents(hashes) => {
+            TransmissionInput::RemoveTorrents(hashes, delete_local_data) => {
                 let tr_client = self.tr_client.as_ref().unwrap();
-                match tr_client.torrent_remove(Some(hashes), false).await {
+                match tr_client
+                    .torrent_remove(Some(hashes), delete_local_data)
+                    .await
+                {
                     Ok(_) => {}
                     Err(err) => {
                         sender
@@ -263,38 +382,231 @@ impl AsyncComponent for Transmission {
                     }
                 }
             }
-            TransmissionInput::SetFilesWanted(hash, torrent_id, wanted, unwanted) => {
+            TransmissionInput::GetDetailsFiles(id) => {
                 let tr_client = self.tr_client.as_ref().unwrap();
-
-                let mutator = TorrentMutator {
-                    files_wanted: if !wanted.is_empty() {
-                        Some(wanted)
-                    } else {
-                        None
-                    },
-                    files_unwanted: if !unwanted.is_empty() {
-                        Some(unwanted)
-                    } else {
-                        None
-                    },
+                match tr_client.torrents_files(Some(vec![id])).await {
+                    Ok(mut files) => {
+                        if let Some(files) = files.pop() {
+                            sender
+                                .output(TransmissionOutput::DetailsFilesChanged(files))
+                                .unwrap();
+                        }
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::GetTorrentDetails(id) => {
+                match self.rpc_client.torrents_details(vec![id]).await {
+                    Ok(mut torrents) => {
+                        if let Some(torrent) = torrents.pop() {
+                            sender
+                                .output(TransmissionOutput::TorrentDetailsChanged(Box::new(
+                                    torrent,
+                                )))
+                                .unwrap();
+                        }
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::GetTrackers(id) => {
+                match self.rpc_client.torrents_trackers(Some(vec![id])).await {
+                    Ok(mut trackers) => {
+                        if let Some(trackers) = trackers.pop() {
+                            sender
+                                .output(TransmissionOutput::TrackersChanged(trackers))
+                                .unwrap();
+                        }
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::GetAllTrackers => {
+                match self.rpc_client.torrents_trackers(None).await {
+                    Ok(trackers) => {
+                        sender
+                            .output(TransmissionOutput::AllTrackersChanged(trackers))
+                            .unwrap();
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::GetPeers(id) => {
+                match self.rpc_client.torrents_peers(vec![id]).await {
+                    Ok(mut peers) => {
+                        if let Some(peers) = peers.pop() {
+                            sender
+                                .output(TransmissionOutput::PeersChanged(peers))
+                                .unwrap();
+                        }
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::GetPieces(id) => {
+                match self.rpc_client.torrents_pieces(vec![id]).await {
+                    Ok(mut pieces) => {
+                        if let Some(pieces) = pieces.pop() {
+                            sender
+                                .output(TransmissionOutput::PiecesChanged(pieces))
+                                .unwrap();
+                        }
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::SetFileSelection(hash, torrent_id, selection) => {
+                let args = TorrentSetArgs {
+                    ids: vec![hash],
+                    files_wanted: non_empty(selection.wanted),
+                    files_unwanted: non_empty(selection.unwanted),
+                    priority_high: non_empty(selection.priority_high),
+                    priority_normal: non_empty(selection.priority_normal),
+                    priority_low: non_empty(selection.priority_low),
                     ..Default::default()
                 };
 
-                match tr_client
-                    .torrent_set(Some(vec![hash.clone()]), mutator)
+                match self.rpc_client.torrent_set(args).await {
+                    Ok(_) => {
+                        // Refresh torrents to get updated info
+                        sender.input(TransmissionInput::UpdateTorrents);
+                        sender.input(TransmissionInput::GetFiles(torrent_id));
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::RenamePath(hash, torrent_id, path, new_name) => {
+                match self
+                    .rpc_client
+                    .torrent_rename_path(hash, path, new_name)
                     .await
                 {
                     Ok(_) => {
-                        // Refresh torrents to get updated info
+                        // Refresh both the torrent name and its file list
                         sender.input(TransmissionInput::UpdateTorrents);
                         sender.input(TransmissionInput::GetFiles(torrent_id));
                     }
+                    // The daemon's reason, such as the new name already being taken on disk
+                    Err(ClientError::TransmissionError(result)) => {
+                        sender
+                            .output(TransmissionOutput::RenamePathFailed(torrent_id, result))
+                            .unwrap();
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::RenamePathFailed(
+                                torrent_id,
+                                err.to_string(),
+                            ))
+                            .unwrap();
+                    }
+                }
+            }
+            TransmissionInput::SetTrackerList(hash, torrent_id, tracker_list) => {
+                match self
+                    .rpc_client
+                    .torrent_set_tracker_list(torrent_id, hash, tracker_list)
+                    .await
+                {
+                    Ok(_) => {}
+                    // The daemon's reason, such as an invalid URL, belongs next to the trackers
+                    Err(ClientError::TransmissionError(result)) => {
+                        sender
+                            .output(TransmissionOutput::TrackerEditFailed(result))
+                            .unwrap();
+                    }
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::TrackerEditFailed(err.to_string()))
+                            .unwrap();
+                    }
+                }
+                sender.input(TransmissionInput::GetTrackers(torrent_id));
+                sender.input(TransmissionInput::GetAllTrackers);
+            }
+            TransmissionInput::SetLabels(hash, labels) => {
+                let args = TorrentSetArgs {
+                    ids: vec![hash],
+                    labels: Some(labels),
+                    ..Default::default()
+                };
+
+                match self.rpc_client.torrent_set(args).await {
+                    Ok(_) => {}
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+                sender.input(TransmissionInput::UpdateTorrents);
+            }
+            TransmissionInput::ReannounceTorrents(hashes) => {
+                let tr_client = self.tr_client.as_ref().unwrap();
+                match tr_client.torrent_reannounce(Some(hashes)).await {
+                    Ok(_) => {}
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+                sender.input(TransmissionInput::UpdateTorrents);
+            }
+            TransmissionInput::VerifyTorrents(hashes) => {
+                let tr_client = self.tr_client.as_ref().unwrap();
+                match tr_client.torrent_verify(Some(hashes)).await {
+                    Ok(_) => {}
                     Err(err) => {
                         sender
                             .output(TransmissionOutput::ConnectionError(err.to_string()))
                             .unwrap();
                     }
                 }
+                sender.input(TransmissionInput::UpdateTorrents);
+            }
+            TransmissionInput::SetLocation(hashes, location, move_data) => {
+                let tr_client = self.tr_client.as_ref().unwrap();
+                match tr_client
+                    .torrent_set_location(Some(hashes), location, move_data)
+                    .await
+                {
+                    Ok(_) => {}
+                    Err(err) => {
+                        sender
+                            .output(TransmissionOutput::ConnectionError(err.to_string()))
+                            .unwrap();
+                    }
+                }
+                sender.input(TransmissionInput::UpdateTorrents);
             }
             TransmissionInput::UpdateSettings => {
                 let tr_client = self.tr_client.as_ref().unwrap();
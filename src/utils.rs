@@ -1,5 +1,47 @@
+use crate::fl;
 use std::path::PathBuf;
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum RenameError {
+    Empty,
+    ContainsSlash,
+    Reserved,
+    Duplicate,
+}
+
+impl RenameError {
+    pub fn message(&self) -> String {
+        match self {
+            RenameError::Empty => fl!("rename-error-empty"),
+            RenameError::ContainsSlash => fl!("rename-error-slash"),
+            RenameError::Reserved => fl!("rename-error-reserved"),
+            RenameError::Duplicate => fl!("rename-error-duplicate"),
+        }
+    }
+}
+
+/// Check a new name for a file, folder or torrent against the names already next to it
+pub fn validate_new_name(new_name: &str, siblings: &[String]) -> Result<(), RenameError> {
+    if new_name.trim().is_empty() {
+        return Err(RenameError::Empty);
+    }
+
+    if new_name.contains('/') {
+        return Err(RenameError::ContainsSlash);
+    }
+
+    // These already mean the folder itself and its parent
+    if new_name == "." || new_name == ".." {
+        return Err(RenameError::Reserved);
+    }
+
+    if siblings.iter().any(|sibling| sibling == new_name) {
+        return Err(RenameError::Duplicate);
+    }
+
+    Ok(())
+}
+
 pub fn open_torrent_location(download_dir: &str, torrent_name: &str) {
     let full_path = PathBuf::from(download_dir).join(torrent_name);
 
@@ -18,3 +60,84 @@ pub fn open_torrent_location(download_dir: &str, torrent_name: &str) {
         |_result| {},
     );
 }
+
+pub fn open_torrent_file(download_dir: &str, file_name: &str) {
+    let full_path = PathBuf::from(download_dir).join(file_name);
+
+    let file = relm4::gtk::gio::File::for_path(&full_path);
+    gtk4::FileLauncher::new(Some(&file)).launch(
+        None::<&gtk4::Window>,
+        None::<&gtk4::gio::Cancellable>,
+        |_result| {},
+    );
+}
+
+/// Format an upload ratio, which the daemon reports as negative before there is one
+pub fn format_ratio(ratio: f32) -> String {
+    if ratio < 0.0 {
+        "—".to_string()
+    } else {
+        format!("{:.2}", ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn siblings() -> Vec<String> {
+        vec!["cover.jpg".to_string(), "notes.txt".to_string()]
+    }
+
+    #[test]
+    fn accepts_a_new_name() {
+        assert_eq!(validate_new_name("back.jpg", &siblings()), Ok(()));
+        assert_eq!(validate_new_name("...", &siblings()), Ok(()));
+        assert_eq!(validate_new_name(".hidden", &siblings()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_empty_names() {
+        assert_eq!(validate_new_name("", &siblings()), Err(RenameError::Empty));
+        assert_eq!(
+            validate_new_name("   ", &siblings()),
+            Err(RenameError::Empty)
+        );
+    }
+
+    #[test]
+    fn rejects_slashes() {
+        assert_eq!(
+            validate_new_name("CD1/01.flac", &siblings()),
+            Err(RenameError::ContainsSlash)
+        );
+    }
+
+    #[test]
+    fn rejects_dot_and_dot_dot() {
+        assert_eq!(
+            validate_new_name(".", &siblings()),
+            Err(RenameError::Reserved)
+        );
+        assert_eq!(
+            validate_new_name("..", &siblings()),
+            Err(RenameError::Reserved)
+        );
+    }
+
+    #[test]
+    fn rejects_names_already_in_the_folder() {
+        assert_eq!(
+            validate_new_name("notes.txt", &siblings()),
+            Err(RenameError::Duplicate)
+        );
+        assert_eq!(validate_new_name("Notes.txt", &siblings()), Ok(()));
+    }
+
+    #[test]
+    fn formats_ratios() {
+        assert_eq!(format_ratio(0.0), "0.00");
+        assert_eq!(format_ratio(1.234), "1.23");
+        assert_eq!(format_ratio(-1.0), "—");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::fl;
+use gtk::prelude::{
+    BoxExt, DialogExt, EditableExt, EntryExt, GtkWindowExt, OrientableExt, WidgetExt,
+};
+use relm4::gtk;
+use relm4::ComponentSender;
+use relm4::RelmWidgetExt;
+use relm4::SimpleComponent;
+
+pub struct ViewDialogModel {
+    dialog: granite::Dialog,
+    name_entry: gtk::Entry,
+    original_name: Option<String>,
+    existing_names: Vec<String>,
+    error: Option<String>,
+    visible: bool,
+}
+
+#[derive(Debug)]
+pub enum ViewDialogInput {
+    Open(Option<String>, Vec<String>), // name of the view being renamed, names already in use
+    Close,
+    NameChanged(String),
+    Submit,
+}
+
+#[derive(Debug)]
+pub enum ViewDialogOutput {
+    NameView(Option<String>, String), // old name if renaming, new name
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for ViewDialogModel {
+    type Init = ();
+    type Input = ViewDialogInput;
+    type Output = ViewDialogOutput;
+
+    view! {
+        #[root]
+        granite::Dialog {
+            #[watch]
+            set_visible: model.visible,
+            #[watch]
+            set_title: Some(&if model.original_name.is_some() {
+                fl!("view-rename-title")
+            } else {
+                fl!("view-save-title")
+            }),
+            set_modal: true,
+            set_default_width: 350,
+            set_hide_on_close: true,
+
+            connect_response[sender] => move |_, response_type| {
+                if response_type == gtk::ResponseType::Ok {
+                    sender.input(ViewDialogInput::Submit);
+                } else {
+                    sender.input(ViewDialogInput::Close);
+                }
+            },
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_margin_bottom: 12,
+                set_margin_start: 12,
+                set_margin_end: 12,
+                set_margin_top: 12,
+                set_spacing: 6,
+
+                #[local_ref]
+                name_entry -> gtk::Entry {
+                    set_placeholder_text: Some(&fl!("view-name-placeholder")),
+                    connect_changed[sender] => move |entry| {
+                        sender.input(ViewDialogInput::NameChanged(entry.text().to_string()));
+                    },
+                    connect_activate => ViewDialogInput::Submit,
+                },
+
+                gtk::Label {
+                    set_halign: gtk::Align::Start,
+                    set_wrap: true,
+                    set_xalign: 0.0,
+                    add_css_class: granite::STYLE_CLASS_SMALL_LABEL,
+                    #[watch]
+                    set_class_active: ("error", model.error.is_some()),
+                    #[watch]
+                    set_class_active: ("dim-label", model.error.is_none()),
+                    #[watch]
+                    set_label: &model.error.clone().unwrap_or_else(|| fl!("view-save-description")),
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> relm4::ComponentParts<Self> {
+        let model = ViewDialogModel {
+            dialog: root.clone(),
+            name_entry: gtk::Entry::new(),
+            original_name: None,
+            existing_names: vec![],
+            error: None,
+            visible: false,
+        };
+
+        let name_entry = &model.name_entry;
+        let widgets = view_output!();
+
+        root.add_button(&fl!("action-cancel"), gtk::ResponseType::Cancel);
+        root.add_button(&fl!("action-save"), gtk::ResponseType::Ok);
+        root.set_response_sensitive(gtk::ResponseType::Ok, false);
+
+        relm4::ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            ViewDialogInput::Open(original_name, existing_names) => {
+                self.existing_names = existing_names;
+                self.name_entry
+                    .set_text(original_name.as_deref().unwrap_or_default());
+                self.original_name = original_name;
+                self.validate();
+                self.visible = true;
+                self.name_entry.grab_focus();
+            }
+            ViewDialogInput::Close => {
+                self.visible = false;
+            }
+            ViewDialogInput::NameChanged(_) => {
+                self.validate();
+            }
+            ViewDialogInput::Submit => {
+                if self.validate() {
+                    let name = self.name_entry.text().trim().to_string();
+                    if self.original_name.as_ref() != Some(&name) {
+                        sender
+                            .output(ViewDialogOutput::NameView(self.original_name.clone(), name))
+                            .unwrap();
+                    }
+                    self.visible = false;
+                }
+            }
+        }
+    }
+}
+
+impl ViewDialogModel {
+    /// Check the name, showing why it can't be used. Keeping the view's own name is fine.
+    fn validate(&mut self) -> bool {
+        let name = self.name_entry.text().trim().to_string();
+        let taken =
+            self.original_name.as_ref() != Some(&name) && self.existing_names.contains(&name);
+        self.error = taken.then(|| fl!("view-name-duplicate"));
+
+        let valid = !name.is_empty() && self.error.is_none();
+        self.dialog
+            .set_response_sensitive(gtk::ResponseType::Ok, valid);
+        valid
+    }
+}
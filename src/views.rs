@@ -0,0 +1,206 @@
+use crate::query::{self, Query};
+use crate::sidebar::TorrentFilter;
+use crate::torrent::TorrentObject;
+use gtk::prelude::{SettingsExt, SettingsExtManual, ToVariant};
+use gtk::{gio, glib};
+use relm4::gtk;
+
+/// A named combination of a sidebar filter, a label and a search, shown in the sidebar and
+/// kept in settings in the order they're listed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedView {
+    pub name: String,
+    pub filter: String, // filter id
+    pub label: String,  // empty for any label
+    pub query: String,  // search text
+}
+
+/// Decides which torrents are shown for one filter, label and search
+#[derive(Debug, Clone)]
+pub struct ViewMatcher {
+    filter: TorrentFilter,
+    label: String,
+    query: Query,
+}
+
+impl ViewMatcher {
+    pub fn new(filter: TorrentFilter, label: String, query: Query) -> Self {
+        ViewMatcher {
+            filter,
+            label,
+            query,
+        }
+    }
+
+    /// `now` is the current Unix time, for searches on when torrents were added
+    pub fn matches(&self, torrent: &TorrentObject, now: i64) -> bool {
+        self.query.matches(torrent, now)
+            && self.filter.matches(torrent)
+            && (self.label.is_empty() || torrent.labels().contains(&self.label))
+    }
+
+    pub fn uses_age(&self) -> bool {
+        self.query.uses_age()
+    }
+}
+
+impl SavedView {
+    pub fn matcher(&self) -> ViewMatcher {
+        // Views are only saved from searches that parsed, so this can only fail if the
+        // settings were edited by hand
+        let query = Query::parse(&self.query).unwrap_or_default();
+        ViewMatcher::new(
+            TorrentFilter::from_id(&self.filter),
+            self.label.clone(),
+            query,
+        )
+    }
+
+    /// A new view showing the torrents of this one that also match `label` and `query`.
+    /// The view only has room for one label, so a second one moves into the search.
+    pub fn narrow(&self, name: String, label: &str, query: &str) -> SavedView {
+        let mut view = SavedView {
+            name,
+            filter: self.filter.clone(),
+            label: self.label.clone(),
+            query: query.trim().to_string(),
+        };
+
+        if !label.is_empty() && label != self.label {
+            if self.label.is_empty() {
+                view.label = label.to_string();
+            } else {
+                view.query = format!("label:{} {}", query::quote(label), view.query);
+            }
+        }
+        view.query = format!("{} {}", self.query, view.query).trim().to_string();
+
+        view
+    }
+}
+
+fn views_to_variant(views: &[SavedView]) -> glib::Variant {
+    views
+        .iter()
+        .map(|view| {
+            (
+                view.name.clone(),
+                view.filter.clone(),
+                view.label.clone(),
+                view.query.clone(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .to_variant()
+}
+
+fn views_from_variant(variant: &glib::Variant) -> Vec<SavedView> {
+    let views: Vec<(String, String, String, String)> = variant.get().unwrap_or_default();
+    views
+        .into_iter()
+        .map(|(name, filter, label, query)| SavedView {
+            name,
+            filter,
+            label,
+            query,
+        })
+        .collect()
+}
+
+pub fn saved_views() -> Vec<SavedView> {
+    let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+    views_from_variant(&settings.value("saved-views"))
+}
+
+pub fn save_views(views: &[SavedView]) {
+    let settings = gio::Settings::new("com.github.davidmhewitt.torrential.settings");
+    if let Err(err) = settings.set("saved-views", views_to_variant(views)) {
+        log::error!("Error saving views: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Condition, Term};
+
+    fn view(name: &str, filter: &str, label: &str, query: &str) -> SavedView {
+        SavedView {
+            name: name.to_string(),
+            filter: filter.to_string(),
+            label: label.to_string(),
+            query: query.to_string(),
+        }
+    }
+
+    #[test]
+    fn views_round_trip_through_settings() {
+        let views = vec![
+            view("Films", "downloading", "films", "size:>4G"),
+            view("Old \"stuff\"", "all", "", "added:>1y -label:\"keep me\""),
+        ];
+
+        let variant = views_to_variant(&views);
+        assert_eq!(variant.type_().as_str(), "a(ssss)");
+        assert_eq!(views_from_variant(&variant), views);
+        assert!(views_from_variant(&views_to_variant(&[])).is_empty());
+    }
+
+    #[test]
+    fn unexpected_settings_load_no_views() {
+        assert!(views_from_variant(&"films".to_variant()).is_empty());
+    }
+
+    #[test]
+    fn invalid_query_matches_like_an_empty_one() {
+        let matcher = view("Broken", "all", "", "label:\"unfinished").matcher();
+        assert_eq!(matcher.query, Query::default());
+        assert_eq!(matcher.filter, TorrentFilter::from_id("all"));
+    }
+
+    #[test]
+    fn narrowing_keeps_the_filter_and_adds_the_search() {
+        let current = view("Films", "downloading", "films", "size:>4G");
+
+        let narrowed = current.narrow("Big films".to_string(), "", "  ratio:<1 ");
+        assert_eq!(
+            narrowed,
+            view("Big films", "downloading", "films", "size:>4G ratio:<1")
+        );
+
+        let narrowed = current.narrow("Same label".to_string(), "films", "");
+        assert_eq!(
+            narrowed,
+            view("Same label", "downloading", "films", "size:>4G")
+        );
+    }
+
+    #[test]
+    fn narrowing_without_a_label_takes_the_new_one() {
+        let current = view("Big", "all", "", "size:>4G");
+        let narrowed = current.narrow("Big films".to_string(), "films", "");
+        assert_eq!(narrowed, view("Big films", "all", "films", "size:>4G"));
+    }
+
+    #[test]
+    fn second_label_moves_into_the_search() {
+        let current = view("Films", "all", "films", "");
+        let label = "director's \"cut\" \\ extras";
+        let narrowed = current.narrow("Cuts".to_string(), label, "bunny");
+
+        assert_eq!(narrowed.label, "films");
+        assert_eq!(
+            Query::parse(&narrowed.query).unwrap().terms(),
+            [
+                Term {
+                    negated: false,
+                    condition: Condition::Label(label.to_lowercase()),
+                },
+                Term {
+                    negated: false,
+                    condition: Condition::Name("bunny".to_string()),
+                },
+            ]
+        );
+    }
+}